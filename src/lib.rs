@@ -3,6 +3,15 @@ extern crate aoc_runner;
 #[macro_use]
 extern crate aoc_runner_derive;
 
+// cargo-aoc serves a single year per library, so the 2020 solutions are
+// built instead of the 2022 ones when the `y2020` feature is on (and so
+// with `--all-features` too)
+#[cfg(feature = "y2020")]
+pub mod y2020;
+#[cfg(not(feature = "y2020"))]
 pub mod y2022;
 
+#[cfg(feature = "y2020")]
+aoc_lib! { year = 2020 }
+#[cfg(not(feature = "y2020"))]
 aoc_lib! { year = 2022 }
@@ -1,43 +1,147 @@
-use std::collections::HashSet;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+// Every set of `k` distinct indices into `values` whose values add up to
+// `target`. Each combination lists its indices in increasing order and the
+// combinations themselves are sorted.
+pub fn k_sum(values: &[u32], target: u64, k: usize) -> Vec<Vec<usize>> {
+    let mut combinations = match k {
+        0 if target == 0 => vec![vec![]],
+        0 => vec![],
+        1 => (0..values.len())
+            .filter(|&i| values[i] as u64 == target)
+            .map(|i| vec![i])
+            .collect(),
+        2 | 3 => {
+            let mut order: Vec<usize> = (0..values.len()).collect();
+            order.sort_by_key(|&i| values[i]);
+            let sorted: Vec<u64> = order.iter().map(|&i| values[i] as u64).collect();
+
+            let mut positions = Vec::new();
+            if k == 2 {
+                two_sum(&sorted, 0, target, &mut |a, b| positions.push(vec![a, b]));
+            } else {
+                for first in 0..sorted.len() {
+                    if sorted[first] > target {
+                        break;
+                    }
+                    two_sum(&sorted, first + 1, target - sorted[first], &mut |a, b| {
+                        positions.push(vec![first, a, b])
+                    });
+                }
+            }
 
-#[aoc_generator(day01)]
-pub fn generate_input(input: &str) -> HashSet<u32> {
-    input.lines().map(|line| line.parse().unwrap()).collect()
+            positions
+                .into_iter()
+                .map(|combination| {
+                    let mut indices: Vec<usize> = combination.iter().map(|&p| order[p]).collect();
+                    indices.sort_unstable();
+                    indices
+                })
+                .collect()
+        }
+        _ => meet_in_the_middle(values, target, k),
+    };
+
+    combinations.sort_unstable();
+    combinations
 }
 
-#[aoc(day01, part1)]
-pub fn solve_part1(input: &HashSet<u32>) -> u32 {
-    let target = 2020;
+// Two pointers over `sorted[start..]`, calling `found` with every pair of
+// positions whose values add up to `target`, runs of equal values included.
+fn two_sum<F>(sorted: &[u64], start: usize, target: u64, found: &mut F)
+where
+    F: FnMut(usize, usize),
+{
+    if sorted.len() < 2 {
+        return;
+    }
 
-    for x in input {
-        let y = target - x;
-        if input.contains(&y) {
-            return x * y;
+    let (mut lo, mut hi) = (start, sorted.len() - 1);
+    while lo < hi {
+        let sum = sorted[lo] + sorted[hi];
+        if sum < target {
+            lo += 1;
+        } else if sum > target {
+            hi -= 1;
+        } else if sorted[lo] == sorted[hi] {
+            // everything between the pointers has the same value
+            for a in lo..hi {
+                for b in a + 1..=hi {
+                    found(a, b);
+                }
+            }
+            return;
+        } else {
+            let lo_end = (lo..hi).find(|&i| sorted[i] != sorted[lo]).unwrap_or(hi);
+            let hi_start = (lo_end..hi)
+                .rev()
+                .find(|&i| sorted[i] != sorted[hi])
+                .map_or(lo_end, |i| i + 1);
+            for a in lo..lo_end {
+                for b in hi_start..=hi {
+                    found(a, b);
+                }
+            }
+            lo = lo_end;
+            hi = hi_start - 1;
         }
     }
-
-    0
 }
 
-#[aoc(day01, part2)]
-pub fn solve_part2(input: &HashSet<u32>) -> u32 {
-    let target = 2020;
-
-    for x in input {
-        for y in input {
-            if x + y > target {
-                continue;
-            }
+// Splits every combination into its lowest `k / 2` indices and the rest, so
+// each one is found exactly once by pairing up the sums of both halves.
+fn meet_in_the_middle(values: &[u32], target: u64, k: usize) -> Vec<Vec<usize>> {
+    let sum =
+        |combination: &[usize]| -> u64 { combination.iter().map(|&i| values[i] as u64).sum() };
+
+    let mut lower_halves: HashMap<u64, Vec<Vec<usize>>> = HashMap::new();
+    for combination in (0..values.len()).combinations(k / 2) {
+        let s = sum(&combination);
+        if s <= target {
+            lower_halves.entry(s).or_default().push(combination);
+        }
+    }
 
-            let z = target - x - y;
+    let mut combinations = Vec::new();
+    for upper in (0..values.len()).combinations(k - k / 2) {
+        let s = sum(&upper);
+        if s > target {
+            continue;
+        }
 
-            if input.contains(&z) {
-                return x * y * z;
+        if let Some(lowers) = lower_halves.get(&(target - s)) {
+            for lower in lowers
+                .iter()
+                .filter(|lower| lower[lower.len() - 1] < upper[0])
+            {
+                combinations.push(lower.iter().chain(upper.iter()).copied().collect());
             }
         }
     }
 
-    0
+    combinations
+}
+
+fn product_of_first(values: &[u32], k: usize) -> u32 {
+    k_sum(values, 2020, k)
+        .first()
+        .map_or(0, |indices| indices.iter().map(|&i| values[i]).product())
+}
+
+#[aoc_generator(day01)]
+pub fn generate_input(input: &str) -> Vec<u32> {
+    input.lines().map(|line| line.parse().unwrap()).collect()
+}
+
+#[aoc(day01, part1)]
+pub fn solve_part1(input: &[u32]) -> u32 {
+    product_of_first(input, 2)
+}
+
+#[aoc(day01, part2)]
+pub fn solve_part2(input: &[u32]) -> u32 {
+    product_of_first(input, 3)
 }
 
 #[cfg(test)]
@@ -45,7 +149,7 @@ mod tests {
     use super::*;
     use std::fs;
 
-    fn get_input() -> HashSet<u32> {
+    fn get_input() -> Vec<u32> {
         let text = "1721
 979
 366
@@ -70,6 +174,52 @@ mod tests {
         assert_eq!(solve_part2(&input), 241861950);
     }
 
+    #[test]
+    fn example_duplicates() {
+        let input = generate_input("1010\n7\n1010\n");
+        assert_eq!(solve_part1(&input), 1020100);
+        assert_eq!(k_sum(&input, 2020, 2), vec![vec![0, 2]]);
+
+        let input = vec![1010, 5, 1010, 1010, 2015];
+        assert_eq!(
+            k_sum(&input, 2020, 2),
+            vec![vec![0, 2], vec![0, 3], vec![1, 4], vec![2, 3]]
+        );
+        assert_eq!(
+            k_sum(&input, 3030, 3),
+            vec![vec![0, 1, 4], vec![0, 2, 3], vec![1, 2, 4], vec![1, 3, 4]]
+        );
+        assert_eq!(
+            k_sum(&input, 4040, 4),
+            vec![vec![0, 1, 2, 4], vec![0, 1, 3, 4], vec![1, 2, 3, 4]]
+        );
+        assert!(k_sum(&input, 4035, 5).is_empty());
+        assert_eq!(k_sum(&input, 5050, 5), vec![vec![0, 1, 2, 3, 4]]);
+        assert_eq!(k_sum(&input, 1010, 1), vec![vec![0], vec![2], vec![3]]);
+        assert_eq!(k_sum(&input, 0, 0), vec![Vec::<usize>::new()]);
+    }
+
+    #[test]
+    fn example_matches_brute_force() {
+        let input = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9];
+
+        for k in 2..=6 {
+            for target in 0..40 {
+                let expected: Vec<Vec<usize>> = (0..input.len())
+                    .combinations(k)
+                    .filter(|c| c.iter().map(|&i| input[i] as u64).sum::<u64>() == target)
+                    .collect();
+                assert_eq!(
+                    k_sum(&input, target, k),
+                    expected,
+                    "k={} target={}",
+                    k,
+                    target
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2020/day1.txt").unwrap();
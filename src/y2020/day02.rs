@@ -1,11 +1,166 @@
 use std::ops::RangeInclusive;
 
+pub trait Policy {
+    fn check(&self, password: &str) -> bool;
+    fn describe(&self) -> String;
+}
+
+// the letter appears a number of times within the bounds
+pub struct CountPolicy {
+    pub letter: char,
+    pub bounds: RangeInclusive<usize>,
+}
+
+impl Policy for CountPolicy {
+    fn check(&self, password: &str) -> bool {
+        self.bounds
+            .contains(&password.chars().filter(|&c| c == self.letter).count())
+    }
+
+    fn describe(&self) -> String {
+        if *self.bounds.end() == usize::MAX {
+            format!("{} at least {} times", self.letter, self.bounds.start())
+        } else {
+            format!(
+                "{} {}-{} times",
+                self.letter,
+                self.bounds.start(),
+                self.bounds.end()
+            )
+        }
+    }
+}
+
+// the letter is at exactly one of two 1-based positions
+pub struct PositionPolicy {
+    pub letter: char,
+    pub positions: (usize, usize),
+}
+
+impl Policy for PositionPolicy {
+    fn check(&self, password: &str) -> bool {
+        let is_letter_at = |position: usize| {
+            position > 0 && password.chars().nth(position - 1) == Some(self.letter)
+        };
+
+        is_letter_at(self.positions.0) ^ is_letter_at(self.positions.1)
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} at exactly one of {} and {}",
+            self.letter, self.positions.0, self.positions.1
+        )
+    }
+}
+
+pub struct AllOf(pub Vec<Box<dyn Policy>>);
+
+impl Policy for AllOf {
+    fn check(&self, password: &str) -> bool {
+        self.0.iter().all(|policy| policy.check(password))
+    }
+
+    fn describe(&self) -> String {
+        let policies: Vec<String> = self.0.iter().map(|p| p.describe()).collect();
+        format!("all of ({})", policies.join(", "))
+    }
+}
+
+pub struct AnyOf(pub Vec<Box<dyn Policy>>);
+
+impl Policy for AnyOf {
+    fn check(&self, password: &str) -> bool {
+        self.0.iter().any(|policy| policy.check(password))
+    }
+
+    fn describe(&self) -> String {
+        let policies: Vec<String> = self.0.iter().map(|p| p.describe()).collect();
+        format!("any of ({})", policies.join(", "))
+    }
+}
+
+pub struct Not(pub Box<dyn Policy>);
+
+impl Policy for Not {
+    fn check(&self, password: &str) -> bool {
+        !self.0.check(password)
+    }
+
+    fn describe(&self) -> String {
+        format!("not ({})", self.0.describe())
+    }
+}
+
+pub struct MinLength(pub usize);
+
+impl Policy for MinLength {
+    fn check(&self, password: &str) -> bool {
+        password.chars().count() >= self.0
+    }
+
+    fn describe(&self) -> String {
+        format!("at least {} characters", self.0)
+    }
+}
+
+pub struct ForbiddenSubstrings(pub Vec<String>);
+
+impl Policy for ForbiddenSubstrings {
+    fn check(&self, password: &str) -> bool {
+        self.0
+            .iter()
+            .all(|forbidden| !password.contains(forbidden.as_str()))
+    }
+
+    fn describe(&self) -> String {
+        format!("none of {:?}", self.0)
+    }
+}
+
+// descriptions of every policy the password breaks
+pub fn broken_policies(password: &str, policies: &[Box<dyn Policy>]) -> Vec<String> {
+    policies
+        .iter()
+        .filter(|policy| !policy.check(password))
+        .map(|policy| policy.describe())
+        .collect()
+}
+
+// every password that breaks at least one policy, along with what it breaks
+pub fn report<'a>(
+    passwords: &[&'a str],
+    policies: &[Box<dyn Policy>],
+) -> Vec<(&'a str, Vec<String>)> {
+    passwords
+        .iter()
+        .map(|&password| (password, broken_policies(password, policies)))
+        .filter(|(_, broken)| !broken.is_empty())
+        .collect()
+}
+
 pub struct PasswordPolicy {
     letter: char,
     bounds: RangeInclusive<usize>,
     password: String,
 }
 
+impl PasswordPolicy {
+    pub fn count_policy(&self) -> CountPolicy {
+        CountPolicy {
+            letter: self.letter,
+            bounds: self.bounds.clone(),
+        }
+    }
+
+    pub fn position_policy(&self) -> PositionPolicy {
+        PositionPolicy {
+            letter: self.letter,
+            positions: (*self.bounds.start(), *self.bounds.end()),
+        }
+    }
+}
+
 #[aoc_generator(day02)]
 pub fn generate_input(input: &str) -> Vec<PasswordPolicy> {
     input
@@ -13,7 +168,7 @@ pub fn generate_input(input: &str) -> Vec<PasswordPolicy> {
         .filter_map(|line: &str| {
             if let [bounds, letter, password] = line.split_whitespace().collect::<Vec<&str>>()[..3]
             {
-                let bounds: Vec<usize> = bounds.split("-").map(|x| x.parse().unwrap()).collect();
+                let bounds: Vec<usize> = bounds.split('-').map(|x| x.parse().unwrap()).collect();
                 let bounds = RangeInclusive::new(bounds[0], bounds[1]);
 
                 let letter: char = letter.chars().next().unwrap();
@@ -31,32 +186,18 @@ pub fn generate_input(input: &str) -> Vec<PasswordPolicy> {
 }
 
 #[aoc(day02, part1)]
-pub fn solve_part1(input: &Vec<PasswordPolicy>) -> usize {
+pub fn solve_part1(input: &[PasswordPolicy]) -> usize {
     input
         .iter()
-        .filter(|policy| {
-            policy.bounds.contains(
-                &policy
-                    .password
-                    .chars()
-                    .filter(|c| c == &policy.letter)
-                    .count(),
-            )
-        })
+        .filter(|entry| entry.count_policy().check(&entry.password))
         .count()
 }
 
 #[aoc(day02, part2)]
-pub fn solve_part2(input: &Vec<PasswordPolicy>) -> usize {
+pub fn solve_part2(input: &[PasswordPolicy]) -> usize {
     input
         .iter()
-        .filter(|policy| {
-            let (lower, upper) = policy.bounds.clone().into_inner();
-            let lower_char = policy.password.chars().nth(lower - 1).unwrap();
-            let upper_char = policy.password.chars().nth(upper - 1).unwrap();
-
-            (lower_char == policy.letter) ^ (upper_char == policy.letter)
-        })
+        .filter(|entry| entry.position_policy().check(&entry.password))
         .count()
 }
 
@@ -87,6 +228,64 @@ mod tests {
         assert_eq!(solve_part2(&input), 1);
     }
 
+    #[test]
+    fn example_policies() {
+        let input = get_input();
+        let entry = &input[1];
+        assert!(!entry.count_policy().check(&entry.password));
+        assert!(!entry.position_policy().check(&entry.password));
+        assert!(!entry.position_policy().check(""));
+        assert_eq!(entry.count_policy().describe(), "b 1-3 times");
+
+        let policies: Vec<Box<dyn Policy>> = vec![
+            Box::new(MinLength(8)),
+            Box::new(ForbiddenSubstrings(vec![
+                "password".to_string(),
+                "123".to_string(),
+            ])),
+            Box::new(AnyOf(vec![
+                Box::new(CountPolicy {
+                    letter: '!',
+                    bounds: 1..=usize::MAX,
+                }),
+                Box::new(CountPolicy {
+                    letter: '?',
+                    bounds: 1..=usize::MAX,
+                }),
+            ])),
+            Box::new(Not(Box::new(AllOf(vec![
+                Box::new(PositionPolicy {
+                    letter: 'a',
+                    positions: (1, 100),
+                }),
+                Box::new(MinLength(10)),
+            ])))),
+        ];
+
+        assert_eq!(broken_policies("correct horse!", &policies).len(), 0);
+        assert_eq!(
+            report(&["pass123", "ok?ok?ok?", "apassword12?"], &policies),
+            vec![
+                (
+                    "pass123",
+                    vec![
+                        "at least 8 characters".to_string(),
+                        "none of [\"password\", \"123\"]".to_string(),
+                        "any of (! at least 1 times, ? at least 1 times)".to_string(),
+                    ]
+                ),
+                (
+                    "apassword12?",
+                    vec![
+                        "none of [\"password\", \"123\"]".to_string(),
+                        "not (all of (a at exactly one of 1 and 100, at least 10 characters))"
+                            .to_string(),
+                    ]
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2020/day2.txt").unwrap();
@@ -1,44 +1,140 @@
-#[aoc_generator(day03)]
-pub fn generate_input(input: &str) -> Vec<String> {
-    input.lines().map(|line| line.to_string()).collect()
+use std::ops::RangeInclusive;
+
+pub type Slope = (usize, usize);
+
+#[derive(Clone, Copy)]
+pub enum Objective {
+    FewestTrees,
+    MostTrees,
+}
+
+// The map as one bitset row per line. It repeats to the right forever.
+pub struct Forest {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    trees: Vec<u64>,
 }
 
-pub fn count_encountered_trees(input: &Vec<String>, col_step: &usize, row_step: &usize) -> usize {
-    let mut row = 0;
-    let mut col = 0;
-    let mut found_trees = 0;
-    let total_rows = input.len();
+impl Forest {
+    pub fn is_tree(&self, row: usize, col: usize) -> bool {
+        let col = col % self.width;
+        let word = self.trees[row * self.words_per_row + col / 64];
+        word & (1 << (col % 64)) != 0
+    }
+
+    // positions visited going `right` and `down` from the top left corner
+    fn path(&self, (right, down): Slope) -> impl Iterator<Item = (usize, usize)> {
+        assert!(down > 0, "the toboggan has to move down");
+
+        (0..self.height)
+            .step_by(down)
+            .enumerate()
+            .map(move |(step, row)| (row, step * right))
+    }
 
-    if total_rows == 0 {
-        return 0;
+    pub fn count_trees(&self, slope: Slope) -> usize {
+        self.path(slope)
+            .filter(|&(row, col)| self.is_tree(row, col))
+            .count()
     }
 
-    let total_cols = input[0].len();
+    pub fn count_trees_for(&self, slopes: &[Slope]) -> Vec<usize> {
+        slopes
+            .iter()
+            .map(|&slope| self.count_trees(slope))
+            .collect()
+    }
 
-    while row < total_rows {
-        if input[row].as_bytes()[col % total_cols] == b'#' {
-            found_trees += 1;
+    // the slope within the bounds that hits the fewest or most trees, the
+    // first one wins ties
+    pub fn best_slope(
+        &self,
+        rights: RangeInclusive<usize>,
+        downs: RangeInclusive<usize>,
+        objective: Objective,
+    ) -> Option<(Slope, usize)> {
+        let mut best: Option<(Slope, usize)> = None;
+
+        for right in rights {
+            for down in downs.clone().filter(|&down| down > 0) {
+                let trees = self.count_trees((right, down));
+                let better = match (best, objective) {
+                    (None, _) => true,
+                    (Some((_, b)), Objective::FewestTrees) => trees < b,
+                    (Some((_, b)), Objective::MostTrees) => trees > b,
+                };
+
+                if better {
+                    best = Some(((right, down), trees));
+                }
+            }
         }
 
-        row += row_step;
-        col += col_step;
+        best
     }
 
-    found_trees
+    // the map with `O` where the path lands on open ground and `X` where it
+    // hits a tree, repeated to the right as far as the path goes
+    pub fn render_path(&self, slope: Slope) -> String {
+        let path: Vec<(usize, usize)> = self.path(slope).collect();
+        let repeats = path.last().map_or(1, |&(_, col)| col / self.width + 1);
+
+        let mut rendered = String::new();
+        for row in 0..self.height {
+            for col in 0..self.width * repeats {
+                let on_path = path.binary_search(&(row, col)).is_ok();
+                rendered.push(match (on_path, self.is_tree(row, col)) {
+                    (true, true) => 'X',
+                    (true, false) => 'O',
+                    (false, true) => '#',
+                    (false, false) => '.',
+                });
+            }
+            rendered.push('\n');
+        }
+
+        rendered
+    }
+}
+
+#[aoc_generator(day03)]
+pub fn generate_input(input: &str) -> Forest {
+    let lines: Vec<&str> = input.lines().collect();
+    let width = lines.first().map_or(0, |line| line.len());
+    assert!(
+        lines.iter().all(|line| line.len() == width),
+        "every row of the map needs the same number of squares"
+    );
+    let width = width.max(1);
+    let words_per_row = width.div_ceil(64);
+
+    let mut trees = vec![0; lines.len() * words_per_row];
+    for (row, line) in lines.iter().enumerate() {
+        for (col, _) in line.bytes().enumerate().filter(|&(_, b)| b == b'#') {
+            trees[row * words_per_row + col / 64] |= 1 << (col % 64);
+        }
+    }
+
+    Forest {
+        width,
+        height: lines.len(),
+        words_per_row,
+        trees,
+    }
 }
 
 #[aoc(day03, part1)]
-pub fn solve_part1(input: &Vec<String>) -> usize {
-    count_encountered_trees(input, &3, &1)
+pub fn solve_part1(input: &Forest) -> usize {
+    input.count_trees((3, 1))
 }
 
 #[aoc(day03, part2)]
-pub fn solve_part2(input: &Vec<String>) -> usize {
-    let slopes: Vec<(usize, usize)> = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
-
-    slopes.iter().fold(1, |acc, (col_step, row_step)| {
-        acc * count_encountered_trees(input, col_step, row_step)
-    })
+pub fn solve_part2(input: &Forest) -> usize {
+    input
+        .count_trees_for(&[(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)])
+        .iter()
+        .product()
 }
 
 #[cfg(test)]
@@ -46,7 +142,7 @@ mod tests {
     use super::*;
     use std::fs;
 
-    fn get_input() -> Vec<String> {
+    fn get_input() -> Forest {
         let text = "..##.........##.........##.........##.........##.........##.......
 #...#...#..#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..
 .#....#..#..#....#..#..#....#..#..#....#..#..#....#..#..#....#..#.
@@ -76,6 +172,61 @@ mod tests {
         assert_eq!(solve_part2(&input), 336);
     }
 
+    #[test]
+    fn example_slopes() {
+        let input = get_input();
+
+        assert_eq!(
+            input.count_trees_for(&[(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]),
+            vec![2, 7, 3, 4, 2]
+        );
+        assert_eq!(
+            input.best_slope(1..=7, 1..=2, Objective::FewestTrees),
+            Some(((5, 2), 0))
+        );
+        assert_eq!(
+            input.best_slope(1..=7, 1..=2, Objective::MostTrees),
+            Some(((3, 1), 7))
+        );
+        assert_eq!(input.best_slope(1..=7, 0..=0, Objective::MostTrees), None);
+    }
+
+    #[test]
+    fn example_render_path() {
+        let input = generate_input(
+            "..##...
+#...#..
+.#....#
+..#.#..",
+        );
+
+        assert_eq!(
+            input.render_path((3, 1)),
+            "O.##.....##...
+#..O#..#...#..
+.#....X.#....#
+..#.#....X.#..
+"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "every row of the map needs the same number of squares")]
+    fn example_ragged_rows() {
+        generate_input(&format!("..\n{}#\n", ".".repeat(70)));
+    }
+
+    #[test]
+    fn example_wide_rows() {
+        // rows spanning several bitset words
+        let row = format!("{}#", ".".repeat(99));
+        let input = generate_input(&format!("{}\n{}\n", row, row));
+        assert!(input.is_tree(1, 99));
+        assert!(input.is_tree(0, 199));
+        assert!(!input.is_tree(0, 64));
+        assert_eq!(input.count_trees((99, 1)), 1);
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2020/day3.txt").unwrap();
@@ -1,135 +1,292 @@
 use lazy_static::lazy_static;
-use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::str::FromStr;
+
+// One rule per line: `<field> <required|optional> [validator args...]`
+pub const PUZZLE_SCHEMA: &str = "byr required year 1920-2002
+iyr required year 2010-2020
+eyr required year 2020-2030
+hgt required height 150-193cm 59-76in
+hcl required hex-color
+ecl required one-of amb blu brn gry grn hzl oth
+pid required digits 9
+cid optional
+";
 
 lazy_static! {
-    static ref BYR_REGEX: Regex = Regex::new(r"byr:(\d+)").unwrap();
-    static ref IYR_REGEX: Regex = Regex::new(r"iyr:(\d+)").unwrap();
-    static ref EYR_REGEX: Regex = Regex::new(r"eyr:(\d+)").unwrap();
-    static ref HGT_REGEX: Regex = Regex::new(r"hgt:(\w+)").unwrap();
-    static ref HCL_REGEX: Regex = Regex::new(r"hcl:(#?\w+)").unwrap();
-    static ref ECL_REGEX: Regex = Regex::new(r"ecl:(\w+)").unwrap();
-    static ref PID_REGEX: Regex = Regex::new(r"pid:(\w+)").unwrap();
-    static ref CID_REGEX: Regex = Regex::new(r"cid:(\w+)").unwrap();
-    static ref HAIR_COLOR_VALIDATOR_REGEX: Regex = Regex::new(r"#[0-9a-f]{6}").unwrap();
-    static ref EYE_COLOR_VALIDATOR_REGEX: Regex =
-        Regex::new(r"(amb|blu|brn|gry|grn|hzl|oth)").unwrap();
+    static ref DEFAULT_SCHEMA: Schema = Schema::default();
 }
 
-#[derive(Debug)]
-pub struct Passport {
-    byr: Option<u32>,
-    iyr: Option<u32>,
-    eyr: Option<u32>,
-    hgt: Option<String>,
-    hcl: Option<String>,
-    ecl: Option<String>,
-    pid: Option<String>,
-    cid: Option<String>,
+#[derive(Debug, PartialEq)]
+pub enum Validator {
+    Any,
+    Year(RangeInclusive<u32>),
+    Height(Vec<(String, RangeInclusive<u32>)>),
+    HexColor,
+    OneOf(Vec<String>),
+    Digits(usize),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Reason {
+    Missing,
+    NotAYear,
+    OutOfRange(u32),
+    UnknownUnit,
+    NotAHexColor,
+    NotAllowed,
+    NotDigits(usize),
 }
 
-fn to_string_value(capture: Option<regex::Captures>) -> Option<String> {
-    match capture {
-        Some(value) => Some(value.get(1)?.as_str().to_string()),
-        None => None,
+#[derive(Debug, PartialEq)]
+pub struct Violation {
+    pub field: String,
+    pub reason: Reason,
+}
+
+fn in_range(value: u32, range: &RangeInclusive<u32>) -> Result<(), Reason> {
+    if range.contains(&value) {
+        Ok(())
+    } else {
+        Err(Reason::OutOfRange(value))
     }
 }
 
-fn to_int_value(capture: Option<regex::Captures>) -> Option<u32> {
-    match capture {
-        Some(value) => value.get(1)?.as_str().parse().ok(),
-        None => None,
+impl Validator {
+    fn check(&self, value: &str) -> Result<(), Reason> {
+        match self {
+            Validator::Any => Ok(()),
+            Validator::Year(range) => {
+                if value.len() != 4 || !value.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(Reason::NotAYear);
+                }
+                in_range(value.parse().unwrap(), range)
+            }
+            Validator::Height(units) => units
+                .iter()
+                .find_map(|(unit, range)| {
+                    let amount = value.strip_suffix(unit.as_str())?;
+                    if amount.is_empty() || !amount.bytes().all(|b| b.is_ascii_digit()) {
+                        return None;
+                    }
+                    Some(in_range(amount.parse().ok()?, range))
+                })
+                .unwrap_or(Err(Reason::UnknownUnit)),
+            Validator::HexColor => match value.strip_prefix('#') {
+                Some(hex)
+                    if hex.len() == 6 && hex.bytes().all(|b| b"0123456789abcdef".contains(&b)) =>
+                {
+                    Ok(())
+                }
+                _ => Err(Reason::NotAHexColor),
+            },
+            Validator::OneOf(allowed) => {
+                if allowed.iter().any(|a| a == value) {
+                    Ok(())
+                } else {
+                    Err(Reason::NotAllowed)
+                }
+            }
+            Validator::Digits(length) => {
+                if value.len() == *length && value.bytes().all(|b| b.is_ascii_digit()) {
+                    Ok(())
+                } else {
+                    Err(Reason::NotDigits(*length))
+                }
+            }
+        }
     }
 }
 
-impl Passport {
-    pub fn new(line: &str) -> Option<Passport> {
-        let byr = BYR_REGEX.captures(&line);
-        let iyr = IYR_REGEX.captures(&line);
-        let eyr = EYR_REGEX.captures(&line);
-        let hgt = HGT_REGEX.captures(&line);
-        let hcl = HCL_REGEX.captures(&line);
-        let ecl = ECL_REGEX.captures(&line);
-        let pid = PID_REGEX.captures(&line);
-        let cid = CID_REGEX.captures(&line);
-
-        let passport = Passport {
-            byr: to_int_value(byr),
-            iyr: to_int_value(iyr),
-            eyr: to_int_value(eyr),
-            hgt: to_string_value(hgt),
-            hcl: to_string_value(hcl),
-            ecl: to_string_value(ecl),
-            pid: to_string_value(pid),
-            cid: to_string_value(cid),
-        };
+fn parse_range(s: &str) -> Option<RangeInclusive<u32>> {
+    let (start, end) = s.split_once('-')?;
+    Some(start.parse().ok()?..=end.parse().ok()?)
+}
 
-        return if passport.has_all_required_fields() {
-            Some(passport)
-        } else {
-            None
-        };
+impl FromStr for Validator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let kind = words.next().unwrap_or("any");
+        let args: Vec<&str> = words.collect();
+
+        Ok(match (kind, &args[..]) {
+            ("any", []) => Validator::Any,
+            ("year", [range]) => {
+                Validator::Year(parse_range(range).ok_or(format!("bad year range {}", range))?)
+            }
+            ("height", units) if !units.is_empty() => Validator::Height(
+                units
+                    .iter()
+                    .map(|arg| {
+                        let split = arg
+                            .find(|c: char| c.is_alphabetic())
+                            .ok_or(format!("height {} has no unit", arg))?;
+                        let range = parse_range(&arg[..split])
+                            .ok_or(format!("bad height range {}", arg))?;
+                        Ok((arg[split..].to_string(), range))
+                    })
+                    .collect::<Result<_, String>>()?,
+            ),
+            ("hex-color", []) => Validator::HexColor,
+            ("one-of", allowed) if !allowed.is_empty() => {
+                Validator::OneOf(allowed.iter().map(|a| a.to_string()).collect())
+            }
+            ("digits", [length]) => Validator::Digits(
+                length
+                    .parse()
+                    .map_err(|_| format!("bad length {}", length))?,
+            ),
+            _ => return Err(format!("unknown validator {}", s)),
+        })
     }
+}
 
-    pub fn has_all_required_fields(&self) -> bool {
-        self.byr.is_some()
-            && self.iyr.is_some()
-            && self.eyr.is_some()
-            && self.hgt.is_some()
-            && self.hcl.is_some()
-            && self.ecl.is_some()
-            && self.pid.is_some()
-    }
-
-    pub fn is_valid(&self) -> bool {
-        (1920..=2002).contains(&self.byr.unwrap())
-            && (2010..=2020).contains(&self.iyr.unwrap())
-            && (2020..=2030).contains(&self.eyr.unwrap())
-            && self.is_height_valid()
-            && self.pid.to_owned().unwrap().len() == 9
-            && HAIR_COLOR_VALIDATOR_REGEX
-                .captures(&self.hcl.to_owned().unwrap())
-                .is_some()
-            && EYE_COLOR_VALIDATOR_REGEX
-                .captures(&self.ecl.to_owned().unwrap())
-                .is_some()
-    }
-
-    fn is_height_valid(&self) -> bool {
-        let height = self.hgt.to_owned().unwrap();
-
-        if height.ends_with("in") {
-            let height = height.replace("in", "");
-            let height: u32 = height.parse().unwrap();
-            return (59..=76).contains(&height);
-        } else if height.ends_with("cm") {
-            let height = height.replace("cm", "");
-            let height: u32 = height.parse().unwrap();
-            return (150..=193).contains(&height);
-        }
-        false
+#[derive(Debug, PartialEq)]
+pub struct FieldRule {
+    pub name: String,
+    pub required: bool,
+    pub validator: Validator,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SchemaError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Schema(Vec<FieldRule>);
+
+impl FromStr for Schema {
+    type Err = SchemaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(index, line)| {
+                let error = |message: String| SchemaError {
+                    line: index + 1,
+                    message,
+                };
+
+                let mut words = line.split_whitespace();
+                let name = words.next().unwrap().to_string();
+                let required = match words.next() {
+                    Some("required") => true,
+                    Some("optional") => false,
+                    other => {
+                        return Err(error(format!(
+                            "expected required or optional, got {:?}",
+                            other
+                        )))
+                    }
+                };
+                let validator = words.collect::<Vec<_>>().join(" ").parse().map_err(error)?;
+
+                Ok(FieldRule {
+                    name,
+                    required,
+                    validator,
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Schema)
+    }
+}
+
+impl Default for Schema {
+    fn default() -> Self {
+        PUZZLE_SCHEMA.parse().unwrap()
+    }
+}
+
+impl Schema {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Schema, SchemaError> {
+        fs::read_to_string(path)
+            .map_err(|e| SchemaError {
+                line: 0,
+                message: e.to_string(),
+            })?
+            .parse()
+    }
+
+    pub fn missing_fields(&self, passport: &Passport) -> Vec<Violation> {
+        self.0
+            .iter()
+            .filter(|rule| rule.required && !passport.fields.contains_key(&rule.name))
+            .map(|rule| Violation {
+                field: rule.name.clone(),
+                reason: Reason::Missing,
+            })
+            .collect()
+    }
+
+    // every field of the passport that breaks the schema, in schema order
+    pub fn validate(&self, passport: &Passport) -> Vec<Violation> {
+        self.0
+            .iter()
+            .filter_map(|rule| {
+                let reason = match passport.fields.get(&rule.name) {
+                    Some(value) => rule.validator.check(value).err()?,
+                    None if rule.required => Reason::Missing,
+                    None => return None,
+                };
+
+                Some(Violation {
+                    field: rule.name.clone(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct Passport {
+    fields: HashMap<String, String>,
+}
+
+impl Passport {
+    pub fn new(record: &str) -> Passport {
+        let fields = record
+            .split_whitespace()
+            .filter_map(|field| field.split_once(':'))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        Passport { fields }
+    }
+
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.fields.get(field).map(|v| v.as_str())
     }
 }
 
 #[aoc_generator(day04)]
 pub fn generate_input(input: &str) -> Vec<Passport> {
-    input
-        .split("\n\n")
-        .filter_map(|line| {
-            let line = line.replace("\n", " ");
-            let passport = Passport::new(&line);
-            passport
-        })
-        .collect()
+    input.split("\n\n").map(Passport::new).collect()
 }
 
 #[aoc(day04, part1)]
-pub fn solve_part1(input: &Vec<Passport>) -> usize {
-    input.len()
+pub fn solve_part1(input: &[Passport]) -> usize {
+    input
+        .iter()
+        .filter(|passport| DEFAULT_SCHEMA.missing_fields(passport).is_empty())
+        .count()
 }
 
 #[aoc(day04, part2)]
-pub fn solve_part2(input: &Vec<Passport>) -> usize {
-    input.iter().filter(|passport| passport.is_valid()).count()
+pub fn solve_part2(input: &[Passport]) -> usize {
+    input
+        .iter()
+        .filter(|passport| DEFAULT_SCHEMA.validate(passport).is_empty())
+        .count()
 }
 
 #[cfg(test)]
@@ -194,12 +351,116 @@ iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
         assert_eq!(solve_part2(&input), 4);
     }
 
+    #[test]
+    fn example_violations() {
+        let schema = Schema::default();
+        let violation = |field: &str, reason| Violation {
+            field: field.to_string(),
+            reason,
+        };
+
+        let input = generate_input(
+            "eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+hgt:59cm ecl:zzz
+eyr:2038 hcl:74454a iyr:2023
+pid:3556412378
+
+ecl:xamb hcl:#12345g byr:02000 hgt:60in",
+        );
+        assert_eq!(input.len(), 3);
+
+        assert_eq!(
+            schema.validate(&input[0]),
+            vec![
+                violation("eyr", Reason::OutOfRange(1972)),
+                violation("hgt", Reason::UnknownUnit),
+                violation("pid", Reason::NotDigits(9)),
+            ]
+        );
+        assert_eq!(
+            schema.validate(&input[1]),
+            vec![
+                violation("byr", Reason::Missing),
+                violation("iyr", Reason::OutOfRange(2023)),
+                violation("eyr", Reason::OutOfRange(2038)),
+                violation("hgt", Reason::OutOfRange(59)),
+                violation("hcl", Reason::NotAHexColor),
+                violation("ecl", Reason::NotAllowed),
+                violation("pid", Reason::NotDigits(9)),
+            ]
+        );
+        assert_eq!(
+            schema.validate(&input[2]),
+            vec![
+                violation("byr", Reason::NotAYear),
+                violation("iyr", Reason::Missing),
+                violation("eyr", Reason::Missing),
+                violation("hcl", Reason::NotAHexColor),
+                violation("ecl", Reason::NotAllowed),
+                violation("pid", Reason::Missing),
+            ]
+        );
+        assert_eq!(schema.missing_fields(&input[0]), vec![]);
+
+        let validator = Validator::Height(vec![("cm".to_string(), 150..=193)]);
+        assert_eq!(validator.check("170cm"), Ok(()));
+        assert_eq!(validator.check("+170cm"), Err(Reason::UnknownUnit));
+        assert_eq!(validator.check("cm"), Err(Reason::UnknownUnit));
+    }
+
+    #[test]
+    fn example_schema_file() {
+        let path = std::env::temp_dir().join("aoc_2020_day04_schema.txt");
+        fs::write(
+            &path,
+            "# only names and eye colors matter here
+name required any
+ecl optional one-of amb blu
+",
+        )
+        .unwrap();
+
+        let schema = Schema::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let input = generate_input("name:sedat ecl:blu\n\necl:grn");
+        assert_eq!(schema.validate(&input[0]), vec![]);
+        assert_eq!(schema.validate(&input[1]).len(), 2);
+        assert_eq!(input[0].get("name"), Some("sedat"));
+
+        assert_eq!(
+            "byr required year 1920".parse::<Schema>(),
+            Err(SchemaError {
+                line: 1,
+                message: "bad year range 1920".to_string()
+            })
+        );
+        assert_eq!(
+            "byr required\n\nhgt mandatory height 150-193cm"
+                .parse::<Schema>()
+                .unwrap_err()
+                .line,
+            3
+        );
+        assert!(Schema::load("input/2020/missing.txt").is_err());
+
+        let spaced: Schema = "  byr  required\tyear  1920-2002\n\tcid optional\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            spaced,
+            "byr required year 1920-2002\ncid optional".parse().unwrap()
+        );
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2020/day4.txt").unwrap();
         let input = generate_input(&text);
         let actual = solve_part1(&input);
-        assert_eq!(196, actual);
+        assert_eq!(208, actual);
     }
 
     #[test]
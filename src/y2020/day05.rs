@@ -1,58 +1,136 @@
-#[aoc_generator(day05)]
-pub fn generate_input(input: &str) -> Vec<u32> {
-    input.lines().map(|line| find_seat(line)).collect()
+// A pass is the seat id written in binary: the first `row_bits` letters are
+// F (0) or B (1) and the remaining `column_bits` ones L (0) or R (1).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane {
+    pub row_bits: u32,
+    pub column_bits: u32,
+}
+
+impl Default for Plane {
+    fn default() -> Self {
+        Plane {
+            row_bits: 7,
+            column_bits: 3,
+        }
+    }
 }
 
-struct SeatRange(u32, u32);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Seat {
+    pub row: u32,
+    pub column: u32,
+    pub id: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PassError {
+    WrongLength { expected: usize, actual: usize },
+    BadLetter { position: usize, letter: char },
+    OutsidePlane { row: u32, column: u32 },
+}
 
-impl SeatRange {
-    pub fn take_upper(&mut self) {
-        self.0 = self.0 + self.diff();
+impl Plane {
+    fn length(&self) -> usize {
+        (self.row_bits + self.column_bits) as usize
     }
 
-    pub fn take_lower(&mut self) {
-        self.1 = self.1 - self.diff();
+    pub fn rows(&self) -> u32 {
+        1 << self.row_bits
     }
 
-    fn diff(&self) -> u32 {
-        ((self.1 as f32 - self.0 as f32) / 2f32).round() as u32
+    pub fn columns(&self) -> u32 {
+        1 << self.column_bits
     }
-}
 
-fn find_seat(seat: &str) -> u32 {
-    let mut row = SeatRange(0, 127);
-    let mut column = SeatRange(0, 7);
-
-    for c in seat.chars() {
-        match c {
-            'F' => row.take_lower(),
-            'B' => row.take_upper(),
-            'L' => column.take_lower(),
-            'R' => column.take_upper(),
-            _ => (),
+    pub fn seat(&self, row: u32, column: u32) -> Result<Seat, PassError> {
+        if row >= self.rows() || column >= self.columns() {
+            return Err(PassError::OutsidePlane { row, column });
         }
+
+        Ok(Seat {
+            row,
+            column,
+            id: row << self.column_bits | column,
+        })
     }
 
-    row.0 * 8 + column.0
-}
+    pub fn decode(&self, pass: &str) -> Result<Seat, PassError> {
+        let length = pass.chars().count();
+        if length != self.length() {
+            return Err(PassError::WrongLength {
+                expected: self.length(),
+                actual: length,
+            });
+        }
 
-#[aoc(day05, part1)]
-pub fn solve_part1(seats: &Vec<u32>) -> u32 {
-    *seats.iter().max().unwrap()
-}
+        let mut id = 0;
+        for (position, letter) in pass.chars().enumerate() {
+            let is_row = position < self.row_bits as usize;
+            let bit = match (is_row, letter) {
+                (true, 'F') | (false, 'L') => 0,
+                (true, 'B') | (false, 'R') => 1,
+                _ => return Err(PassError::BadLetter { position, letter }),
+            };
+            id = id << 1 | bit;
+        }
 
-#[aoc(day05, part2)]
-pub fn solve_part2(seats: &Vec<u32>) -> u32 {
-    let mut seats = seats.clone();
-    seats.sort();
+        self.seat(id >> self.column_bits, id & (self.columns() - 1))
+    }
+
+    pub fn encode(&self, row: u32, column: u32) -> Result<String, PassError> {
+        let seat = self.seat(row, column)?;
+
+        Ok((0..self.length())
+            .map(|position| {
+                let bit = seat.id >> (self.length() - 1 - position) & 1;
+                match (position < self.row_bits as usize, bit) {
+                    (true, 0) => 'F',
+                    (true, _) => 'B',
+                    (false, 0) => 'L',
+                    (false, _) => 'R',
+                }
+            })
+            .collect())
+    }
 
-    for (seat, next_seat) in seats.iter().zip(seats.iter().skip(1)) {
-        if seat + 1 != *next_seat {
-            return seat + 1;
+    // ids between the lowest and highest taken seat that nobody has a pass for
+    pub fn missing_seats(&self, seats: &[Seat]) -> Vec<u32> {
+        let mut taken = vec![false; (self.rows() * self.columns()) as usize];
+        for seat in seats {
+            taken[seat.id as usize] = true;
         }
+
+        let first = seats.iter().map(|s| s.id).min().unwrap_or(0);
+        let last = seats.iter().map(|s| s.id).max().unwrap_or(0);
+
+        (first..last).filter(|&id| !taken[id as usize]).collect()
     }
+}
+
+#[aoc_generator(day05)]
+pub fn generate_input(input: &str) -> Vec<Seat> {
+    let plane = Plane::default();
 
-    0
+    input
+        .lines()
+        .map(|line| plane.decode(line).expect("invalid boarding pass"))
+        .collect()
+}
+
+#[aoc(day05, part1)]
+pub fn solve_part1(seats: &[Seat]) -> u32 {
+    seats.iter().map(|seat| seat.id).max().unwrap()
+}
+
+#[aoc(day05, part2)]
+pub fn solve_part2(seats: &[Seat]) -> u32 {
+    let missing = Plane::default().missing_seats(seats);
+
+    // ours is the only gap with taken seats on both sides
+    *missing
+        .iter()
+        .find(|&&id| !missing.contains(&(id - 1)) && !missing.contains(&(id + 1)))
+        .unwrap()
 }
 
 #[cfg(test)]
@@ -79,6 +157,73 @@ mod tests {
         sample_4: ("BBFFBBFRLL", 820),
     }
 
+    #[test]
+    fn example_decode() {
+        let plane = Plane::default();
+        assert_eq!(
+            plane.decode("FBFBBFFRLR"),
+            Ok(Seat {
+                row: 44,
+                column: 5,
+                id: 357
+            })
+        );
+        assert_eq!(plane.encode(44, 5), Ok("FBFBBFFRLR".to_string()));
+        assert_eq!(
+            plane.decode("FBFBBFFRL"),
+            Err(PassError::WrongLength {
+                expected: 10,
+                actual: 9
+            })
+        );
+        assert_eq!(
+            plane.decode("FBFBBFFRLF"),
+            Err(PassError::BadLetter {
+                position: 9,
+                letter: 'F'
+            })
+        );
+        assert_eq!(
+            plane.decode("FBFBBFRRLR"),
+            Err(PassError::BadLetter {
+                position: 6,
+                letter: 'R'
+            })
+        );
+        assert_eq!(
+            plane.encode(128, 0),
+            Err(PassError::OutsidePlane {
+                row: 128,
+                column: 0
+            })
+        );
+    }
+
+    #[test]
+    fn example_custom_plane() {
+        let plane = Plane {
+            row_bits: 3,
+            column_bits: 2,
+        };
+
+        for row in 0..plane.rows() {
+            for column in 0..plane.columns() {
+                let pass = plane.encode(row, column).unwrap();
+                let seat = plane.decode(&pass).unwrap();
+                assert_eq!(
+                    (seat.row, seat.column, seat.id),
+                    (row, column, row * 4 + column)
+                );
+            }
+        }
+
+        let seats: Vec<Seat> = ["FFBLR", "FBFLL", "FBFRL", "FBBLR"]
+            .iter()
+            .map(|pass| plane.decode(pass).unwrap())
+            .collect();
+        assert_eq!(plane.missing_seats(&seats), vec![6, 7, 9, 11, 12]);
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2020/day5.txt").unwrap();
@@ -1,45 +1,232 @@
-use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+const QUESTIONS: usize = 26;
+
+// every person's answers as a bitmask, bit 0 being question `a`
+pub struct Group(Vec<u32>);
+
+impl Group {
+    fn yes_counts(&self) -> [usize; QUESTIONS] {
+        let mut counts = [0; QUESTIONS];
+        for person in &self.0 {
+            for (question, count) in counts.iter_mut().enumerate() {
+                *count += (person >> question & 1) as usize;
+            }
+        }
+        counts
+    }
+
+    pub fn size(&self) -> usize {
+        self.0.len()
+    }
+}
+
+// Which questions of a group to count, e.g. `all`, `at_least(2) & !all` or
+// `exactly(1) | none`.
+#[derive(Debug, PartialEq)]
+pub enum Query {
+    Any,
+    All,
+    None,
+    Exactly(usize),
+    AtLeast(usize),
+    AtMost(usize),
+    Not(Box<Query>),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+}
+
+impl Query {
+    // bitmask of the questions in the group that match the query; nothing
+    // matches in a group without people, not even `all` or `none`
+    pub fn evaluate(&self, group: &Group) -> u32 {
+        if group.size() == 0 {
+            return 0;
+        }
+
+        self.matching(&group.yes_counts(), group.size())
+    }
+
+    fn matching(&self, counts: &[usize; QUESTIONS], size: usize) -> u32 {
+        let questions_where = |predicate: &dyn Fn(usize) -> bool| {
+            counts
+                .iter()
+                .enumerate()
+                .filter(|&(_, &count)| predicate(count))
+                .fold(0, |mask, (question, _)| mask | 1 << question)
+        };
+
+        match self {
+            Query::Any => questions_where(&|count| count > 0),
+            Query::All => questions_where(&|count| count == size),
+            Query::None => questions_where(&|count| count == 0),
+            Query::Exactly(n) => questions_where(&|count| count == *n),
+            Query::AtLeast(n) => questions_where(&|count| count >= *n),
+            Query::AtMost(n) => questions_where(&|count| count <= *n),
+            Query::Not(query) => !query.matching(counts, size) & ((1 << QUESTIONS) - 1),
+            Query::And(a, b) => a.matching(counts, size) & b.matching(counts, size),
+            Query::Or(a, b) => a.matching(counts, size) | b.matching(counts, size),
+        }
+    }
+
+    pub fn sum(&self, groups: &[Group]) -> usize {
+        groups
+            .iter()
+            .map(|group| self.evaluate(group).count_ones() as usize)
+            .sum()
+    }
+
+    // for every question, in how many groups it matches the query
+    pub fn histogram(&self, groups: &[Group]) -> [usize; QUESTIONS] {
+        let mut histogram = [0; QUESTIONS];
+        for group in groups {
+            let mask = self.evaluate(group);
+            for (question, count) in histogram.iter_mut().enumerate() {
+                *count += (mask >> question & 1) as usize;
+            }
+        }
+        histogram
+    }
+}
+
+struct QueryParser<'a> {
+    tokens: Vec<&'a str>,
+    position: usize,
+}
+
+impl<'a> QueryParser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            token => Err(format!("expected {}, got {:?}", expected, token)),
+        }
+    }
+
+    // expression := term ("|" term)*
+    fn expression(&mut self) -> Result<Query, String> {
+        let mut query = self.term()?;
+        while self.peek() == Some("|") {
+            self.next();
+            query = Query::Or(Box::new(query), Box::new(self.term()?));
+        }
+        Ok(query)
+    }
+
+    // term := factor ("&" factor)*
+    fn term(&mut self) -> Result<Query, String> {
+        let mut query = self.factor()?;
+        while self.peek() == Some("&") {
+            self.next();
+            query = Query::And(Box::new(query), Box::new(self.factor()?));
+        }
+        Ok(query)
+    }
+
+    // factor := "!" factor | "(" expression ")" | name | name "(" number ")"
+    fn factor(&mut self) -> Result<Query, String> {
+        match self.next() {
+            Some("!") => Ok(Query::Not(Box::new(self.factor()?))),
+            Some("(") => {
+                let query = self.expression()?;
+                self.expect(")")?;
+                Ok(query)
+            }
+            Some("any") => Ok(Query::Any),
+            Some("all") => Ok(Query::All),
+            Some("none") => Ok(Query::None),
+            Some(name @ ("exactly" | "at_least" | "at_most")) => {
+                self.expect("(")?;
+                let n = self
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or(format!("{} needs a number", name))?;
+                self.expect(")")?;
+
+                Ok(match name {
+                    "exactly" => Query::Exactly(n),
+                    "at_least" => Query::AtLeast(n),
+                    _ => Query::AtMost(n),
+                })
+            }
+            token => Err(format!("unexpected {:?}", token)),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Vec::new();
+        let mut start = None;
+        for (i, c) in s.char_indices() {
+            let is_word = c.is_alphanumeric() || c == '_';
+            if is_word && start.is_none() {
+                start = Some(i);
+            } else if !is_word {
+                if let Some(begin) = start.take() {
+                    tokens.push(&s[begin..i]);
+                }
+                if !c.is_whitespace() {
+                    tokens.push(&s[i..i + c.len_utf8()]);
+                }
+            }
+        }
+        if let Some(begin) = start {
+            tokens.push(&s[begin..]);
+        }
+
+        let mut parser = QueryParser {
+            tokens,
+            position: 0,
+        };
+        let query = parser.expression()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some(token) => Err(format!("unexpected {:?}", token)),
+        }
+    }
+}
 
 #[aoc_generator(day06)]
-pub fn generate_input(input: &str) -> Vec<Vec<String>> {
+pub fn generate_input(input: &str) -> Vec<Group> {
     input
         .split("\n\n")
-        .map(|group| group.lines().map(|x| x.to_string()).collect())
+        .map(|group| {
+            Group(
+                group
+                    .lines()
+                    .filter(|person| !person.is_empty())
+                    .map(|person| {
+                        person
+                            .bytes()
+                            .filter(|b| b.is_ascii_lowercase())
+                            .fold(0, |mask, b| mask | 1 << (b - b'a'))
+                    })
+                    .collect(),
+            )
+        })
         .collect()
 }
 
 #[aoc(day06, part1)]
-pub fn solve_part1(input: &Vec<Vec<String>>) -> usize {
-    input
-        .iter()
-        .map(|group| group.join("").chars().collect::<HashSet<char>>().len())
-        .sum()
+pub fn solve_part1(input: &[Group]) -> usize {
+    Query::Any.sum(input)
 }
 
 #[aoc(day06, part2)]
-pub fn solve_part2(input: &Vec<Vec<String>>) -> usize {
-    input
-        .iter()
-        .map(|group| {
-            let mut yes_answers_counter: HashMap<char, u32> = HashMap::new();
-            let group_size = group.len() as u32;
-
-            for person in group {
-                for yes_answer in person.chars() {
-                    if let Some(a) = yes_answers_counter.get_mut(&yes_answer) {
-                        *a += 1;
-                    } else {
-                        yes_answers_counter.insert(yes_answer, 1);
-                    }
-                }
-            }
-
-            yes_answers_counter
-                .values()
-                .filter(|&answer| *answer == group_size)
-                .count()
-        })
-        .sum()
+pub fn solve_part2(input: &[Group]) -> usize {
+    Query::All.sum(input)
 }
 
 #[cfg(test)]
@@ -47,7 +234,7 @@ mod tests {
     use super::*;
     use std::fs;
 
-    fn get_input() -> Vec<Vec<String>> {
+    fn get_input() -> Vec<Group> {
         let text = "abc
 
 a
@@ -78,6 +265,56 @@ b";
         assert_eq!(solve_part2(&input), 6);
     }
 
+    #[test]
+    fn example_queries() {
+        let input = get_input();
+        let sum = |query: &str| query.parse::<Query>().unwrap().sum(&input);
+
+        assert_eq!(sum("any"), 11);
+        assert_eq!(sum("all"), 6);
+        assert_eq!(sum("none"), 5 * 26 - 11);
+        assert_eq!(sum("exactly(1)"), 3 + 3 + 2 + 1);
+        assert_eq!(sum("at_least(2)"), 1 + 1);
+        assert_eq!(sum("any & !all"), 11 - 6);
+        assert_eq!(sum("exactly(1) | at_least(4)"), 9 + 1);
+        assert_eq!(sum("!(any | none)"), 0);
+
+        let histogram = "all".parse::<Query>().unwrap().histogram(&input);
+        assert_eq!(&histogram[..4], &[3, 2, 1, 0]);
+        assert_eq!(histogram.iter().sum::<usize>(), 6);
+
+        assert_eq!(
+            "at_least(2) & !all".parse(),
+            Ok(Query::And(
+                Box::new(Query::AtLeast(2)),
+                Box::new(Query::Not(Box::new(Query::All)))
+            ))
+        );
+        assert!("at_least(x)".parse::<Query>().is_err());
+        assert!("any all".parse::<Query>().is_err());
+        assert!("(any".parse::<Query>().is_err());
+    }
+
+    #[test]
+    fn example_empty_groups() {
+        let input = generate_input("a\n\n");
+
+        assert_eq!(solve_part1(&input), 1);
+        assert_eq!(solve_part2(&input), 1);
+        for query in &["none", "!all", "at_most(1)", "exactly(0)"] {
+            let query: Query = query.parse().unwrap();
+            assert_eq!(query.evaluate(&input[1]), 0);
+        }
+
+        let input = generate_input("ab\n\n\n\nb\n\n\nbc\n");
+        assert_eq!(
+            input.iter().map(Group::size).collect::<Vec<_>>(),
+            vec![1, 0, 1, 1]
+        );
+        assert_eq!(solve_part2(&input), 2 + 1 + 2);
+        assert_eq!(Query::None.sum(&input), 24 + 25 + 24);
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2020/day6.txt").unwrap();
@@ -1,107 +1,289 @@
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 lazy_static! {
-    static ref BAG_RULE_REGEX: Regex = Regex::new(r"(\d) ([^,.]*) bag").unwrap();
+    static ref BAG_RULE_REGEX: Regex = Regex::new(r"(\d+) ([^,.]*) bag").unwrap();
 }
 
-fn for_each_capture<F>(input: &str, mut f: F)
-where
-    F: FnMut(&str, String, usize),
-{
-    for line in input.lines() {
-        if let Some((parent_color, rest)) = get_color_and_rest(line) {
-            for capture in BAG_RULE_REGEX.captures_iter(&rest) {
-                let amount = capture[1].to_string().parse().unwrap();
-                let child_color = capture[2].to_string();
+#[derive(Debug, PartialEq)]
+pub enum BagError {
+    UnknownColor(String),
+    // colors along the containment loop, starting and ending with the same one
+    CyclicContainment(Vec<String>),
+}
 
-                f(parent_color, child_color, amount);
-            }
+// which part of the graph to export
+pub enum Scope<'a> {
+    All,
+    // the color and everything it can contain
+    ReachableFrom(&'a str),
+    // the color and everything that can contain it
+    ReachableTo(&'a str),
+}
+
+#[derive(Clone, Copy)]
+enum Visit {
+    New,
+    InProgress,
+    Done(usize),
+}
+
+// Bag rules as a weighted directed graph: an edge from a color to another
+// means the first bag directly contains `count` of the second.
+#[derive(Default)]
+pub struct BagGraph {
+    colors: Vec<String>,
+    ids: HashMap<String, usize>,
+    contains: Vec<Vec<(usize, usize)>>,
+    contained_in: Vec<Vec<usize>>,
+}
+
+impl BagGraph {
+    fn intern(&mut self, color: &str) -> usize {
+        if let Some(&id) = self.ids.get(color) {
+            return id;
         }
+
+        let id = self.colors.len();
+        self.colors.push(color.to_string());
+        self.ids.insert(color.to_string(), id);
+        self.contains.push(Vec::new());
+        self.contained_in.push(Vec::new());
+        id
     }
-}
 
-fn get_color_and_rest(line: &str) -> Option<(&str, &str)> {
-    if let [color, rest] = line.split(" bags contain ").collect::<Vec<&str>>()[..2] {
-        Some((&color, &rest))
-    } else {
-        None
+    fn add_rule(&mut self, parent_color: &str, child_color: &str, count: usize) {
+        let parent = self.intern(parent_color);
+        let child = self.intern(child_color);
+
+        self.contains[parent].push((child, count));
+        self.contained_in[child].push(parent);
     }
-}
 
-#[aoc_generator(day07, part1)]
-pub fn generate_input_part1(input: &str) -> HashMap<String, HashSet<String>> {
-    let mut bags_in: HashMap<String, HashSet<String>> = HashMap::new();
+    pub fn id(&self, color: &str) -> Result<usize, BagError> {
+        self.ids
+            .get(color)
+            .copied()
+            .ok_or_else(|| BagError::UnknownColor(color.to_string()))
+    }
 
-    for_each_capture(input, |parent_color, child_color, _amount| {
-        if let Some(child_rule) = bags_in.get_mut(&child_color) {
-            child_rule.insert(parent_color.to_string());
-        } else {
-            let mut child_rule = HashSet::new();
-            child_rule.insert(parent_color.clone().to_string());
-            bags_in.insert(child_color, child_rule);
+    pub fn color(&self, id: usize) -> &str {
+        &self.colors[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    fn reachable_ids<F>(&self, start: usize, next: F) -> Vec<bool>
+    where
+        F: Fn(usize) -> Vec<usize>,
+    {
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![start];
+
+        while let Some(id) = stack.pop() {
+            for other in next(id) {
+                if !seen[other] {
+                    seen[other] = true;
+                    stack.push(other);
+                }
+            }
         }
-    });
 
-    bags_in
-}
+        seen
+    }
 
-#[aoc(day07, part1)]
-pub fn solve_part1(input: &HashMap<String, HashSet<String>>) -> usize {
-    let mut containing_bags: HashSet<String> = HashSet::new();
+    fn reachable<F>(&self, color: &str, next: F) -> Result<Vec<&str>, BagError>
+    where
+        F: Fn(usize) -> Vec<usize>,
+    {
+        let seen = self.reachable_ids(self.id(color)?, next);
 
-    all_containing_colors(input, "shiny gold", &mut containing_bags);
+        let mut colors: Vec<&str> = (0..self.len())
+            .filter(|&id| seen[id])
+            .map(|id| self.color(id))
+            .collect();
+        colors.sort_unstable();
 
-    containing_bags.len()
-}
+        Ok(colors)
+    }
+
+    // every color that can eventually contain a bag of `color`
+    pub fn ancestors(&self, color: &str) -> Result<Vec<&str>, BagError> {
+        self.reachable(color, |id| self.contained_in[id].clone())
+    }
+
+    // every color that can eventually be found inside a bag of `color`
+    pub fn descendants(&self, color: &str) -> Result<Vec<&str>, BagError> {
+        self.reachable(color, |id| {
+            self.contains[id].iter().map(|&(child, _)| child).collect()
+        })
+    }
+
+    // how many bags a single bag of `color` holds in total
+    pub fn total_bags_inside(&self, color: &str) -> Result<usize, BagError> {
+        let id = self.id(color)?;
+        let mut visits = vec![Visit::New; self.len()];
 
-pub fn all_containing_colors(
-    input: &HashMap<String, HashSet<String>>,
-    color: &str,
-    containing_bags: &mut HashSet<String>,
-) {
-    if let Some(parents) = input.get(color) {
-        for color in parents {
-            containing_bags.insert(color.to_string());
+        self.count_inside(id, &mut visits, &mut Vec::new())
+    }
+
+    pub fn check_acyclic(&self) -> Result<(), BagError> {
+        let mut visits = vec![Visit::New; self.len()];
 
-            all_containing_colors(input, color, containing_bags);
+        for id in 0..self.len() {
+            self.count_inside(id, &mut visits, &mut Vec::new())?;
         }
+
+        Ok(())
     }
-}
 
-#[aoc_generator(day07, part2)]
-pub fn generate_input_part2(input: &str) -> HashMap<String, HashMap<String, usize>> {
-    let mut bags_contain: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    fn count_inside(
+        &self,
+        id: usize,
+        visits: &mut Vec<Visit>,
+        path: &mut Vec<usize>,
+    ) -> Result<usize, BagError> {
+        match visits[id] {
+            Visit::Done(total) => return Ok(total),
+            Visit::InProgress => {
+                let start = path.iter().position(|&p| p == id).unwrap();
+                let cycle = path[start..]
+                    .iter()
+                    .chain(std::iter::once(&id))
+                    .map(|&p| self.color(p).to_string())
+                    .collect();
+                return Err(BagError::CyclicContainment(cycle));
+            }
+            Visit::New => (),
+        }
+
+        visits[id] = Visit::InProgress;
+        path.push(id);
 
-    for_each_capture(input, |parent_color, child_color, amount| {
-        if let Some(contain) = bags_contain.get_mut(parent_color) {
-            contain.insert(child_color, amount);
+        let mut total = 0;
+        for &(child, count) in &self.contains[id] {
+            total += count * (1 + self.count_inside(child, visits, path)?);
+        }
+
+        path.pop();
+        visits[id] = Visit::Done(total);
+
+        Ok(total)
+    }
+
+    fn scope_ids(&self, scope: &Scope) -> Result<Vec<bool>, BagError> {
+        let (color, forward) = match scope {
+            Scope::All => return Ok(vec![true; self.len()]),
+            Scope::ReachableFrom(color) => (color, true),
+            Scope::ReachableTo(color) => (color, false),
+        };
+
+        let start = self.id(color)?;
+        let mut included = if forward {
+            self.reachable_ids(start, |id| {
+                self.contains[id].iter().map(|&(child, _)| child).collect()
+            })
         } else {
-            let mut contain = HashMap::new();
-            contain.insert(child_color, amount);
-            bags_contain.insert(parent_color.clone().to_string(), contain);
+            self.reachable_ids(start, |id| self.contained_in[id].clone())
+        };
+        included[start] = true;
+
+        Ok(included)
+    }
+
+    // Graphviz rendering of the rules, edges are labelled with bag counts
+    pub fn to_dot(&self, highlight: Option<&str>, scope: &Scope) -> Result<String, BagError> {
+        let included = self.scope_ids(scope)?;
+        let highlight = highlight.map(|color| self.id(color)).transpose()?;
+
+        let mut dot = String::from("digraph bags {\n");
+        for id in (0..self.len()).filter(|&id| included[id]) {
+            if Some(id) == highlight {
+                dot += &format!(
+                    "  {} [style=filled, fillcolor=gold];\n",
+                    quote(self.color(id))
+                );
+            } else {
+                dot += &format!("  {};\n", quote(self.color(id)));
+            }
+        }
+        for id in (0..self.len()).filter(|&id| included[id]) {
+            for &(child, count) in self.contains[id].iter().filter(|&&(c, _)| included[c]) {
+                dot += &format!(
+                    "  {} -> {} [label=\"{}\"];\n",
+                    quote(self.color(id)),
+                    quote(self.color(child)),
+                    count
+                );
+            }
         }
-    });
+        dot += "}\n";
 
-    bags_contain
+        Ok(dot)
+    }
+
+    // adjacency as a JSON object: `{"parent": {"child": count, ...}, ...}`
+    pub fn to_json(&self, scope: &Scope) -> Result<String, BagError> {
+        let included = self.scope_ids(scope)?;
+
+        let colors: Vec<String> = (0..self.len())
+            .filter(|&id| included[id])
+            .map(|id| {
+                let children: Vec<String> = self.contains[id]
+                    .iter()
+                    .filter(|&&(child, _)| included[child])
+                    .map(|&(child, count)| format!("{}: {}", quote(self.color(child)), count))
+                    .collect();
+                format!("{}: {{{}}}", quote(self.color(id)), children.join(", "))
+            })
+            .collect();
+
+        Ok(format!("{{{}}}", colors.join(", ")))
+    }
 }
 
-#[aoc(day07, part2)]
-pub fn solve_part2(input: &HashMap<String, HashMap<String, usize>>) -> usize {
-    count_total_bags(input, "shiny gold")
+// a double quoted string, valid in both DOT and JSON
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn get_color_and_rest(line: &str) -> Option<(&str, &str)> {
+    line.split_once(" bags contain ")
 }
 
-fn count_total_bags(input: &HashMap<String, HashMap<String, usize>>, color: &str) -> usize {
-    let mut total = 0;
+#[aoc_generator(day07)]
+pub fn generate_input(input: &str) -> BagGraph {
+    let mut graph = BagGraph::default();
 
-    if let Some(rule) = input.get(color) {
-        for (color, amount) in rule {
-            total += amount + (amount * count_total_bags(input, color));
+    for line in input.lines() {
+        if let Some((parent_color, rest)) = get_color_and_rest(line) {
+            graph.intern(parent_color);
+
+            for capture in BAG_RULE_REGEX.captures_iter(rest) {
+                let amount = capture[1].parse().unwrap();
+                graph.add_rule(parent_color, &capture[2], amount);
+            }
         }
     }
 
-    total
+    graph
+}
+
+#[aoc(day07, part1)]
+pub fn solve_part1(input: &BagGraph) -> usize {
+    input.ancestors("shiny gold").unwrap().len()
+}
+
+#[aoc(day07, part2)]
+pub fn solve_part2(input: &BagGraph) -> usize {
+    input.total_bags_inside("shiny gold").unwrap()
 }
 
 #[cfg(test)]
@@ -111,7 +293,7 @@ mod tests {
 
     #[test]
     fn example_part1() {
-        let input = generate_input_part1(
+        let input = generate_input(
             "light red bags contain 1 bright white bag, 2 muted yellow bags.
 dark orange bags contain 3 bright white bags, 4 muted yellow bags.
 bright white bags contain 1 shiny gold bag.
@@ -129,7 +311,7 @@ dotted black bags contain no other bags.",
 
     #[test]
     fn example_part2_1() {
-        let input = generate_input_part2(
+        let input = generate_input(
             "light red bags contain 1 bright white bag, 2 muted yellow bags.
 dark orange bags contain 3 bright white bags, 4 muted yellow bags.
 bright white bags contain 1 shiny gold bag.
@@ -147,7 +329,7 @@ dotted black bags contain no other bags.",
 
     #[test]
     fn example_part2_2() {
-        let input = generate_input_part2(
+        let input = generate_input(
             "shiny gold bags contain 2 dark red bags.
 dark red bags contain 2 dark orange bags.
 dark orange bags contain 2 dark yellow bags.
@@ -162,10 +344,113 @@ dark violet bags contain no other bags.
         assert_eq!(actual, 126);
     }
 
+    #[test]
+    fn example_queries() {
+        let input = generate_input(
+            "light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.",
+        );
+
+        assert_eq!(input.len(), 9);
+        assert_eq!(
+            input.ancestors("faded blue").unwrap(),
+            vec![
+                "bright white",
+                "dark olive",
+                "dark orange",
+                "light red",
+                "muted yellow",
+                "shiny gold",
+                "vibrant plum"
+            ]
+        );
+        assert_eq!(
+            input.descendants("muted yellow").unwrap(),
+            vec![
+                "dark olive",
+                "dotted black",
+                "faded blue",
+                "shiny gold",
+                "vibrant plum"
+            ]
+        );
+        assert_eq!(input.ancestors("light red").unwrap(), Vec::<&str>::new());
+        assert_eq!(input.total_bags_inside("dark olive"), Ok(7));
+        assert_eq!(input.total_bags_inside("light red"), Ok(186));
+        assert_eq!(
+            input.total_bags_inside("mauve"),
+            Err(BagError::UnknownColor("mauve".to_string()))
+        );
+        assert_eq!(input.check_acyclic(), Ok(()));
+    }
+
+    #[test]
+    fn example_cyclic() {
+        let input = generate_input(
+            "shiny gold bags contain 2 dark red bags.
+dark red bags contain 12 dark orange bags, 1 faded blue bag.
+dark orange bags contain 1 shiny gold bag.
+faded blue bags contain no other bags.
+",
+        );
+
+        let cycle = BagError::CyclicContainment(vec![
+            "shiny gold".to_string(),
+            "dark red".to_string(),
+            "dark orange".to_string(),
+            "shiny gold".to_string(),
+        ]);
+        assert_eq!(input.total_bags_inside("shiny gold"), Err(cycle));
+        assert!(input.check_acyclic().is_err());
+        assert_eq!(input.total_bags_inside("faded blue"), Ok(0));
+        assert_eq!(input.ancestors("shiny gold").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn example_export() {
+        let input = generate_input(
+            "light red bags contain 1 bright white bag, 2 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag.
+dark olive bags contain 3 faded blue bags.
+faded blue bags contain no other bags.",
+        );
+
+        assert_eq!(
+            input
+                .to_dot(Some("shiny gold"), &Scope::ReachableFrom("shiny gold"))
+                .unwrap(),
+            "digraph bags {
+  \"shiny gold\" [style=filled, fillcolor=gold];
+  \"faded blue\";
+  \"dark olive\";
+  \"shiny gold\" -> \"dark olive\" [label=\"1\"];
+  \"dark olive\" -> \"faded blue\" [label=\"3\"];
+}
+"
+        );
+        assert_eq!(
+            input.to_json(&Scope::ReachableTo("shiny gold")).unwrap(),
+            r#"{"light red": {"bright white": 1, "muted yellow": 2}, "bright white": {"shiny gold": 1}, "muted yellow": {"shiny gold": 2}, "shiny gold": {}}"#
+        );
+        assert_eq!(input.to_dot(None, &Scope::All).unwrap().lines().count(), 15);
+        assert!(input.to_json(&Scope::ReachableTo("mauve")).is_err());
+        assert!(input.to_dot(Some("mauve"), &Scope::All).is_err());
+        assert_eq!(quote(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2020/day7.txt").unwrap();
-        let input = generate_input_part1(&text);
+        let input = generate_input(&text);
         let actual = solve_part1(&input);
         assert_eq!(372, actual);
     }
@@ -173,7 +458,7 @@ dark violet bags contain no other bags.
     #[test]
     fn test_input_part2() {
         let text = fs::read_to_string("input/2020/day7.txt").unwrap();
-        let input = generate_input_part2(&text);
+        let input = generate_input(&text);
         let actual = solve_part2(&input);
         assert_eq!(8015, actual);
     }
@@ -47,7 +47,7 @@ impl GameConsole {
         }
     }
     fn execute(&mut self, counter: u32, instruction: &Instruction) -> Option<u32> {
-        if let Some(_) = self.processed.get(&counter) {
+        if self.processed.contains(&counter) {
             return None;
         }
 
@@ -60,7 +60,7 @@ impl GameConsole {
         self.pc = counter;
 
         self.processed.insert(self.pc);
-        self.instruction_stack.push(instruction.clone());
+        self.instruction_stack.push(*instruction);
 
         Some(pc)
     }
@@ -72,7 +72,7 @@ pub fn generate_input(input: &str) -> Vec<Instruction> {
 }
 
 #[aoc(day08, part1)]
-pub fn solve_part1(instructions: &Vec<Instruction>) -> i32 {
+pub fn solve_part1(instructions: &[Instruction]) -> i32 {
     let length: i32 = instructions.len() as i32;
     let mut i: i32 = 0;
     let mut console = GameConsole::new();
@@ -89,11 +89,11 @@ pub fn solve_part1(instructions: &Vec<Instruction>) -> i32 {
 }
 
 #[aoc(day08, part2)]
-pub fn solve_part2(instructions: &Vec<Instruction>) -> i32 {
+pub fn solve_part2(instructions: &[Instruction]) -> i32 {
     let length: i32 = instructions.len() as i32;
 
     'outer: for n in 0..length {
-        let mut swapped: Vec<Instruction> = instructions.clone();
+        let mut swapped: Vec<Instruction> = instructions.to_vec();
         let _old: Vec<_> = swapped
             .splice(
                 n as usize..(n + 1) as usize,
@@ -1,68 +1,113 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+
 #[aoc_generator(day09)]
 pub fn generate_input(input: &str) -> Vec<u64> {
     input.lines().map(|l| l.parse().unwrap()).collect()
 }
 
-fn find_noncompliant_number(input: &[u64], preamble: usize) -> u64 {
-    let windows = input.windows(preamble);
-    let after_preamble = input.iter().skip(preamble);
-    for (&target_number, window) in after_preamble.zip(windows) {
-        let found_pair = window.iter().enumerate().any(|(i, &current_number)| {
-            if target_number > current_number {
-                let diff = target_number - current_number;
-                return window[(i + 1)..].contains(&diff);
+// Keeps the last `preamble` numbers along with a multiset of the sums of
+// every pair of them, so checking a number is a single lookup.
+pub struct XmasValidator {
+    preamble: usize,
+    window: VecDeque<u64>,
+    sums: HashMap<u64, usize>,
+}
+
+impl XmasValidator {
+    pub fn new(preamble: usize) -> Self {
+        XmasValidator {
+            preamble,
+            window: VecDeque::with_capacity(preamble),
+            sums: HashMap::new(),
+        }
+    }
+
+    // `None` while the preamble is being read, otherwise whether the number
+    // is the sum of two of the previous ones
+    pub fn push(&mut self, number: u64) -> Option<bool> {
+        let valid = if self.window.len() == self.preamble {
+            let valid = self.sums.contains_key(&number);
+
+            let oldest = self.window.pop_front().unwrap();
+            for &other in &self.window {
+                if let Entry::Occupied(mut count) = self.sums.entry(oldest + other) {
+                    *count.get_mut() -= 1;
+                    if *count.get() == 0 {
+                        count.remove();
+                    }
+                }
             }
-            false
-        });
 
-        if !found_pair {
-            return target_number;
+            Some(valid)
+        } else {
+            None
+        };
+
+        for &other in &self.window {
+            *self.sums.entry(number + other).or_insert(0) += 1;
         }
+        self.window.push_back(number);
+
+        valid
     }
+}
+
+// every number that isn't the sum of two of the `preamble` numbers before it
+pub fn invalid_numbers<I>(numbers: I, preamble: usize) -> impl Iterator<Item = u64>
+where
+    I: IntoIterator<Item = u64>,
+{
+    let mut validator = XmasValidator::new(preamble);
+
+    numbers
+        .into_iter()
+        .filter(move |&number| validator.push(number) == Some(false))
+}
 
-    0
+fn find_noncompliant_number(input: &[u64], preamble: usize) -> Option<u64> {
+    invalid_numbers(input.iter().copied(), preamble).next()
 }
 
 #[aoc(day09, part1)]
 pub fn solve_part1(input: &[u64]) -> u64 {
-    find_noncompliant_number(input, 25)
+    find_noncompliant_number(input, 25).expect("every number is valid")
 }
 
 fn add_min_max(input: &[u64]) -> u64 {
-    let mut max = input[0];
-    let mut min = input[0];
-
-    for value in input {
-        let value = *value;
-        if value > max {
-            max = value;
-        } else if value < min {
-            min = value;
+    input.iter().min().unwrap() + input.iter().max().unwrap()
+}
+
+// Bounds of the first run of at least two contiguous numbers adding up to
+// `target`, found by sliding both ends of the run forward.
+pub fn find_contiguous_range(input: &[u64], target: u64) -> Option<Range<usize>> {
+    let mut start = 0;
+    let mut sum = 0;
+
+    for (end, &number) in input.iter().enumerate() {
+        sum += number;
+        while sum > target && start < end {
+            sum -= input[start];
+            start += 1;
+        }
+
+        if sum == target && end > start {
+            return Some(start..end + 1);
         }
     }
 
-    return min + max;
+    None
 }
 
 #[aoc(day09, part2)]
 pub fn solve_part2(input: &[u64]) -> u64 {
-    let noncompliant_number: u64 = find_noncompliant_number(input, 25);
-    find_encryption_weakness(input, noncompliant_number)
+    let noncompliant_number = solve_part1(input);
+    find_encryption_weakness(input, noncompliant_number).expect("no weakness found")
 }
 
-fn find_encryption_weakness(input: &[u64], noncompliant_number: u64) -> u64 {
-    let length = input.len();
-
-    for i in 0..length {
-        let mut sum = input[i];
-        for j in i + 1..length {
-            sum += input[j];
-            if sum == noncompliant_number {
-                return add_min_max(&input[i..j]);
-            }
-        }
-    }
-    0
+fn find_encryption_weakness(input: &[u64], noncompliant_number: u64) -> Option<u64> {
+    find_contiguous_range(input, noncompliant_number).map(|range| add_min_max(&input[range]))
 }
 
 #[cfg(test)]
@@ -99,16 +144,45 @@ mod tests {
         let input = get_input();
 
         let actual = find_noncompliant_number(&input, 5);
-        assert_eq!(actual, 127)
+        assert_eq!(actual, Some(127))
     }
 
     #[test]
     fn example_part2() {
         let input = get_input();
 
-        let noncompliant_number = find_noncompliant_number(&input, 5);
+        let noncompliant_number = find_noncompliant_number(&input, 5).unwrap();
         let actual = find_encryption_weakness(&input, noncompliant_number);
-        assert_eq!(actual, 62)
+        assert_eq!(actual, Some(62))
+    }
+
+    #[test]
+    fn example_streaming() {
+        let input = get_input();
+
+        let invalid: Vec<u64> = invalid_numbers(input.iter().copied(), 5).collect();
+        assert_eq!(invalid, vec![127]);
+        let invalid: Vec<u64> = invalid_numbers(input.iter().copied(), 2).collect();
+        assert_eq!(invalid.len(), 18);
+        assert_eq!(find_noncompliant_number(&[1, 2, 3, 5, 8, 13], 2), None);
+
+        // equal numbers at different positions form a pair
+        let mut validator = XmasValidator::new(2);
+        assert_eq!(validator.push(4), None);
+        assert_eq!(validator.push(4), None);
+        assert_eq!(validator.push(8), Some(true));
+        assert_eq!(validator.push(8), Some(false));
+        assert_eq!(validator.push(16), Some(true));
+    }
+
+    #[test]
+    fn example_contiguous_range() {
+        let input = get_input();
+
+        assert_eq!(find_contiguous_range(&input, 127), Some(2..6));
+        assert_eq!(find_contiguous_range(&input, 36), None);
+        assert_eq!(find_contiguous_range(&input, 55), Some(0..2));
+        assert_eq!(find_contiguous_range(&input, 576 + 309), Some(16..19));
     }
 
     #[test]
@@ -1,62 +1,148 @@
-use std::collections::{HashMap, HashSet};
+use num_bigint::BigUint;
+use std::collections::{BTreeMap, VecDeque};
 
+pub const PUZZLE_GAPS: [u64; 3] = [1, 2, 3];
+
+#[derive(Debug, PartialEq)]
+pub enum ChainError {
+    GapNotAllowed { from: u64, to: u64 },
+}
+
+// the outlet and every adapter in increasing order
 #[aoc_generator(day10)]
 pub fn generate_input(input: &str) -> Vec<u64> {
     let mut input: Vec<u64> = input.lines().map(|l| l.parse().unwrap()).collect();
-    input.sort();
-    input.push(input.last().unwrap() + 3);
+    input.sort_unstable();
     input.insert(0, 0);
     input
 }
 
-#[aoc(day10, part1)]
-pub fn solve_part1(input: &Vec<u64>) -> u64 {
-    let mut ones = 0;
-    let mut threes = 0;
-    for (low, high) in input.iter().zip(input[1..].iter()) {
-        let diff = high - low;
-        match diff {
-            1 => ones += 1,
-            3 => threes += 1,
-            _ => (),
+// The adapters followed by the device, whose built-in adapter is rated the
+// largest allowed gap above the highest one.
+fn with_device(adapters: &[u64], gaps: &[u64]) -> Vec<u64> {
+    let max_gap = gaps.iter().copied().max().unwrap_or(0);
+    let mut joltages = adapters.to_vec();
+    joltages.push(adapters.last().copied().unwrap_or(0) + max_gap);
+    joltages
+}
+
+// how often each difference occurs when every adapter is used
+pub fn difference_histogram(
+    adapters: &[u64],
+    gaps: &[u64],
+) -> Result<BTreeMap<u64, usize>, ChainError> {
+    let joltages = with_device(adapters, gaps);
+    let mut histogram = BTreeMap::new();
+
+    for pair in joltages.windows(2) {
+        let difference = pair[1] - pair[0];
+        if !gaps.contains(&difference) {
+            return Err(ChainError::GapNotAllowed {
+                from: pair[0],
+                to: pair[1],
+            });
         }
+        *histogram.entry(difference).or_insert(0) += 1;
     }
-    ones * threes
+
+    Ok(histogram)
+}
+
+#[aoc(day10, part1)]
+pub fn solve_part1(input: &[u64]) -> usize {
+    let histogram = difference_histogram(input, &PUZZLE_GAPS).unwrap();
+    histogram.get(&1).unwrap_or(&0) * histogram.get(&3).unwrap_or(&0)
 }
 
-fn total_combinations(
-    joltages: &HashSet<u64>,
-    jolt: u64,
-    device_jolt: u64,
-    memo: &mut HashMap<u64, u64>,
-) -> u64 {
-    if jolt == device_jolt {
-        return 1;
+// indices of the adapters that can follow the one at `index`, in order
+fn successors<'a>(
+    joltages: &'a [u64],
+    gaps: &'a [u64],
+    index: usize,
+) -> impl Iterator<Item = usize> + 'a {
+    let max_gap = gaps.iter().copied().max().unwrap_or(0);
+
+    (index + 1..joltages.len())
+        .take_while(move |&next| joltages[next] - joltages[index] <= max_gap)
+        .filter(move |&next| gaps.contains(&(joltages[next] - joltages[index])))
+}
+
+// `chains[i]` is the number of ways to get from adapter `i` to the device
+fn chains_to_device(joltages: &[u64], gaps: &[u64]) -> Vec<BigUint> {
+    let mut chains = vec![BigUint::from(0u32); joltages.len()];
+    if let Some(last) = chains.last_mut() {
+        *last = BigUint::from(1u32);
     }
 
-    if let Some(c) = memo.get(&jolt) {
-        return *c;
+    for index in (0..joltages.len().saturating_sub(1)).rev() {
+        chains[index] = successors(joltages, gaps, index)
+            .map(|next| &chains[next])
+            .sum();
     }
 
-    let mut combinations = 0;
+    chains
+}
+
+// Ways to reach each adapter from the outlet, keeping only the adapters
+// close enough to the current one to still be a step away from it.
+pub fn count_arrangements(adapters: &[u64], gaps: &[u64]) -> BigUint {
+    let joltages = with_device(adapters, gaps);
+    let max_gap = gaps.iter().copied().max().unwrap_or(0);
+    let mut window: VecDeque<(u64, BigUint)> = VecDeque::with_capacity(max_gap as usize + 1);
 
-    for j in jolt + 1..jolt + 4 {
-        if let Some(_) = joltages.get(&j) {
-            combinations += total_combinations(joltages, j, device_jolt, memo);
+    for (index, &joltage) in joltages.iter().enumerate() {
+        while window
+            .front()
+            .is_some_and(|&(previous, _)| joltage - previous > max_gap)
+        {
+            window.pop_front();
         }
+
+        let ways = if index == 0 {
+            BigUint::from(1u32)
+        } else {
+            window
+                .iter()
+                .filter(|(previous, _)| gaps.contains(&(joltage - previous)))
+                .map(|(_, ways)| ways)
+                .sum()
+        };
+        window.push_back((joltage, ways));
     }
 
-    memo.insert(jolt, combinations);
-    combinations
+    window.pop_back().map(|(_, ways)| ways).unwrap_or_default()
 }
 
-#[aoc(day10, part2)]
-pub fn solve_part2(input: &Vec<u64>) -> u64 {
-    let device_jolt = input[input.len() - 1];
-    let joltages: HashSet<u64> = input.iter().map(|&x| x).collect();
+// The `k`th (from 0) arrangement from the outlet to the device, when all of
+// them are sorted lexicographically by joltage.
+pub fn kth_arrangement(adapters: &[u64], gaps: &[u64], k: &BigUint) -> Option<Vec<u64>> {
+    let joltages = with_device(adapters, gaps);
+    let chains = chains_to_device(&joltages, gaps);
+    if k >= &chains[0] {
+        return None;
+    }
+
+    let mut k = k.clone();
+    let mut index = 0;
+    let mut arrangement = vec![joltages[0]];
+
+    while index + 1 < joltages.len() {
+        for next in successors(&joltages, gaps, index) {
+            if k < chains[next] {
+                index = next;
+                break;
+            }
+            k -= &chains[next];
+        }
+        arrangement.push(joltages[index]);
+    }
 
-    let mut memo: HashMap<u64, u64> = HashMap::new();
-    total_combinations(&joltages, 0, device_jolt, &mut memo)
+    Some(arrangement)
+}
+
+#[aoc(day10, part2)]
+pub fn solve_part2(input: &[u64]) -> BigUint {
+    count_arrangements(input, &PUZZLE_GAPS)
 }
 
 #[cfg(test)]
@@ -135,7 +221,7 @@ mod tests {
         let input = get_input_1();
 
         let actual = solve_part2(&input);
-        assert_eq!(actual, 8);
+        assert_eq!(actual, BigUint::from(8u32));
     }
 
     #[test]
@@ -143,7 +229,95 @@ mod tests {
         let input = get_input_2();
 
         let actual = solve_part2(&input);
-        assert_eq!(actual, 19208);
+        assert_eq!(actual, BigUint::from(19208u32));
+    }
+
+    #[test]
+    fn example_histogram() {
+        let input = get_input_1();
+
+        let histogram = difference_histogram(&input, &PUZZLE_GAPS).unwrap();
+        assert_eq!(
+            histogram.into_iter().collect::<Vec<_>>(),
+            vec![(1, 7), (3, 5)]
+        );
+        assert_eq!(
+            difference_histogram(&input, &[1, 2]),
+            Err(ChainError::GapNotAllowed { from: 1, to: 4 })
+        );
+
+        let input = generate_input("2\n4\n9\n11");
+        let histogram = difference_histogram(&input, &[2, 3, 5]).unwrap();
+        assert_eq!(
+            histogram.into_iter().collect::<Vec<_>>(),
+            vec![(2, 3), (5, 2)]
+        );
+    }
+
+    #[test]
+    fn example_custom_gaps() {
+        let input = generate_input("2\n4\n6\n7\n9\n11");
+        assert_eq!(count_arrangements(&input, &[2]), BigUint::from(0u32));
+        assert_eq!(count_arrangements(&input, &[2, 3]), BigUint::from(2u32));
+        assert_eq!(count_arrangements(&input, &[1, 2, 3]), BigUint::from(3u32));
+
+        // without a gap of 3 the device sits the largest gap above the adapters
+        let input = generate_input("2\n4\n6");
+        assert_eq!(count_arrangements(&input, &[2]), BigUint::from(1u32));
+        assert_eq!(
+            kth_arrangement(&input, &[2], &BigUint::from(0u32)),
+            Some(vec![0, 2, 4, 6, 8])
+        );
+        assert_eq!(
+            difference_histogram(&input, &[2])
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![(2, 4)]
+        );
+        assert_eq!(count_arrangements(&input, &[2, 4]), BigUint::from(3u32));
+    }
+
+    #[test]
+    fn example_kth_arrangement() {
+        let input = get_input_1();
+        let arrangement = |k: u32| kth_arrangement(&input, &PUZZLE_GAPS, &BigUint::from(k));
+
+        assert_eq!(
+            arrangement(0),
+            Some(vec![0, 1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19, 22])
+        );
+        assert_eq!(
+            arrangement(7),
+            Some(vec![0, 1, 4, 7, 10, 12, 15, 16, 19, 22])
+        );
+        assert_eq!(arrangement(8), None);
+
+        let all: Vec<Vec<u64>> = (0..8).map(|k| arrangement(k).unwrap()).collect();
+        let mut sorted = all.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(all, sorted);
+    }
+
+    #[test]
+    fn example_big_counts() {
+        // a few hundred adapters one jolt apart overflow any machine integer
+        let text: Vec<String> = (1..=300).map(|j| j.to_string()).collect();
+        let input = generate_input(&text.join("\n"));
+
+        let count = count_arrangements(&input, &PUZZLE_GAPS);
+        assert!(count.bits() > 128);
+        assert_eq!(
+            count,
+            chains_to_device(&with_device(&input, &PUZZLE_GAPS), &PUZZLE_GAPS)[0]
+        );
+        assert_eq!(
+            kth_arrangement(&input, &PUZZLE_GAPS, &(count - 1u32))
+                .unwrap()
+                .len(),
+            102
+        );
     }
 
     #[test]
@@ -159,6 +333,6 @@ mod tests {
         let text = fs::read_to_string("input/2020/day10.txt").unwrap();
         let input = generate_input(&text);
         let actual = solve_part2(&input);
-        assert_eq!(386869246296064, actual);
+        assert_eq!(BigUint::from(386869246296064u64), actual);
     }
 }
@@ -9,7 +9,7 @@ static DIRECTIONS: [(isize, isize); 8] = [
     (1, -1),
 ];
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Seat {
     Empty,
     Occupied,
@@ -27,115 +27,388 @@ impl From<char> for Seat {
     }
 }
 
-type Layout = Vec<Vec<Seat>>;
+pub struct Grid<C> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<C>,
+}
 
-#[aoc_generator(day11)]
-pub fn generate_input(input: &str) -> Layout {
-    input
-        .lines()
-        .map(|l| l.chars().map(Seat::from).collect())
-        .collect()
+impl<C: Copy> Grid<C> {
+    fn new(layout: &[Vec<C>]) -> Self {
+        let cols = layout.first().map_or(0, |row| row.len());
+        assert!(
+            layout.iter().all(|row| row.len() == cols),
+            "every row of the layout needs the same number of cells"
+        );
+
+        Grid {
+            rows: layout.len(),
+            cols,
+            cells: layout.iter().flatten().copied().collect(),
+        }
+    }
+}
+
+impl<C> Grid<C> {
+    fn index(&self, row: isize, col: isize) -> Option<usize> {
+        if row < 0 || col < 0 || row as usize >= self.rows || col as usize >= self.cols {
+            return None;
+        }
+
+        Some(row as usize * self.cols + col as usize)
+    }
+}
+
+impl<C: Copy> Grid<C> {
+    pub fn get(&self, row: isize, col: isize) -> Option<C> {
+        self.index(row, col).map(|index| self.cells[index])
+    }
+}
+
+pub enum Neighborhood<C> {
+    // the eight surrounding cells
+    Adjacent,
+    // the first cell in each of the eight directions that isn't see-through
+    LineOfSight(fn(C) -> bool),
+    // cells at arbitrary relative offsets
+    Custom(Vec<(isize, isize)>),
+}
+
+impl<C: Copy> Neighborhood<C> {
+    // cell indices of the neighbors of the cell at `(row, col)`
+    fn indices(&self, grid: &Grid<C>, (row, col): (isize, isize), indices: &mut Vec<usize>) {
+        indices.clear();
+
+        match self {
+            Neighborhood::Adjacent => indices.extend(
+                DIRECTIONS
+                    .iter()
+                    .filter_map(|&(dr, dc)| grid.index(row + dr, col + dc)),
+            ),
+            Neighborhood::LineOfSight(transparent) => {
+                indices.extend(DIRECTIONS.iter().filter_map(|&(dr, dc)| {
+                    (1..)
+                        .map(|distance| grid.index(row + dr * distance, col + dc * distance))
+                        .find(|index| index.is_none_or(|i| !transparent(grid.cells[i])))
+                        .flatten()
+                }))
+            }
+            Neighborhood::Custom(offsets) => indices.extend(
+                offsets
+                    .iter()
+                    .filter_map(|&(dr, dc)| grid.index(row + dr, col + dc)),
+            ),
+        }
+    }
 }
 
-fn find_final_seating<F>(layout: &Layout, get_next_seat: F) -> u32
+#[derive(Debug, Eq, PartialEq)]
+pub enum Termination {
+    FixedPoint,
+    Cycle { period: usize },
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Run {
+    // generations until the first state that repeats
+    pub generations: usize,
+    pub termination: Termination,
+}
+
+pub struct Automaton<C, R> {
+    grid: Grid<C>,
+    next: Vec<C>,
+    // scratch space reused by every step and run
+    indices: Vec<usize>,
+    neighbors: Vec<C>,
+    saved: Vec<C>,
+    replay: Vec<C>,
+    neighborhood: Neighborhood<C>,
+    rule: R,
+}
+
+impl<C, R> Automaton<C, R>
 where
-    F: Fn(&Layout, Seat, (usize, usize)) -> Seat,
+    C: Copy + Eq,
+    R: Fn(C, &[C]) -> C,
 {
-    let rows = layout.len();
-    let cols = layout[0].len();
-
-    let mut layout = layout.clone();
-
-    loop {
-        let mut next_layout = Vec::with_capacity(rows);
-        let mut has_layout_changed = false;
-        for i in 0..rows {
-            let mut next_row = Vec::with_capacity(cols);
-            for j in 0..cols {
-                let seat = layout[i][j];
-                if seat == Seat::Floor {
-                    next_row.push(seat);
-                    continue;
-                }
+    pub fn new(layout: &[Vec<C>], neighborhood: Neighborhood<C>, rule: R) -> Self {
+        let grid = Grid::new(layout);
+
+        Automaton {
+            next: grid.cells.clone(),
+            indices: Vec::with_capacity(DIRECTIONS.len()),
+            neighbors: Vec::with_capacity(DIRECTIONS.len()),
+            saved: grid.cells.clone(),
+            replay: grid.cells.clone(),
+            grid,
+            neighborhood,
+            rule,
+        }
+    }
+
+    // returns whether any cell changed
+    pub fn step(&mut self) -> bool {
+        let mut changed = false;
+
+        for (index, next) in self.next.iter_mut().enumerate() {
+            let position = (
+                (index / self.grid.cols) as isize,
+                (index % self.grid.cols) as isize,
+            );
+            self.neighborhood
+                .indices(&self.grid, position, &mut self.indices);
+            self.neighbors.clear();
+            let cells = &self.grid.cells;
+            self.neighbors
+                .extend(self.indices.iter().map(|&i| cells[i]));
+
+            let cell = cells[index];
+            *next = (self.rule)(cell, &self.neighbors);
+            changed |= *next != cell;
+        }
+
+        std::mem::swap(&mut self.grid.cells, &mut self.next);
+        changed
+    }
+
+    // steps `cells` instead of the automaton's own state
+    fn step_other(&mut self, cells: &mut Vec<C>) -> bool {
+        std::mem::swap(&mut self.grid.cells, cells);
+        let changed = self.step();
+        std::mem::swap(&mut self.grid.cells, cells);
+        changed
+    }
 
-                let next_seat = get_next_seat(&layout, seat, (i, j));
+    // Runs until a state repeats, leaving the automaton in that state. The
+    // period comes from Brent's algorithm, comparing against one saved state
+    // at a time, and the start of the cycle from replaying the run with a
+    // second copy that is `period` generations ahead.
+    pub fn run(&mut self) -> Run {
+        let mut saved = std::mem::take(&mut self.saved);
+        let mut replay = std::mem::take(&mut self.replay);
+        saved.copy_from_slice(&self.grid.cells);
+        replay.copy_from_slice(&self.grid.cells);
 
-                has_layout_changed |= seat != next_seat;
+        let run = self.run_from(&mut saved, &mut replay);
+        self.saved = saved;
+        self.replay = replay;
+        run
+    }
+
+    // `saved` and `behind` both hold the current state to begin with
+    fn run_from(&mut self, saved: &mut Vec<C>, behind: &mut Vec<C>) -> Run {
+        let (mut power, mut period) = (1, 1);
 
-                next_row.push(next_seat);
+        let mut generation = 0;
+        loop {
+            if !self.step() {
+                return Run {
+                    generations: generation,
+                    termination: Termination::FixedPoint,
+                };
+            }
+            generation += 1;
+
+            if self.grid.cells == *saved {
+                break;
+            }
+            if period == power {
+                saved.copy_from_slice(&self.grid.cells);
+                power *= 2;
+                period = 0;
             }
-            next_layout.push(next_row);
+            period += 1;
+        }
+
+        let ahead = saved;
+        ahead.copy_from_slice(behind);
+        for _ in 0..period {
+            self.step_other(ahead);
+        }
+
+        let mut start = 0;
+        while behind != ahead {
+            self.step_other(behind);
+            self.step_other(ahead);
+            start += 1;
         }
+        std::mem::swap(&mut self.grid.cells, behind);
 
-        if !has_layout_changed {
-            break;
+        Run {
+            generations: start,
+            termination: Termination::Cycle { period },
         }
-        layout = next_layout;
     }
 
-    layout
-        .iter()
-        .flat_map(|row| row.iter())
-        .filter(|&&s| s == Seat::Occupied)
-        .count() as u32
+    pub fn count(&self, cell: C) -> usize {
+        self.grid.cells.iter().filter(|&&c| c == cell).count()
+    }
 }
 
-#[aoc(day11, part1)]
-pub fn solve_part1(input_layout: &Layout) -> u32 {
-    find_final_seating(input_layout, |layout, seat, (row, column)| {
-        let total_occupied_adjacent_seats = DIRECTIONS
-            .iter()
-            .map(|&(r, c)| ((r + row as isize) as usize, (c + column as isize) as usize))
-            .filter_map(|(nr, nc)| layout.get(nr).and_then(|row| row.get(nc)))
-            .filter(|&&c| c == Seat::Occupied)
-            .count();
+// Seats never move, so the neighbors of every seat can be resolved once from
+// the floor plan. Seats are stored in a compact adjacency array and only the
+// seats next to one that changed are re-evaluated in the following round.
+pub struct SeatGraph {
+    // neighbors of seat `i` are `neighbors[starts[i]..starts[i + 1]]`
+    starts: Vec<usize>,
+    neighbors: Vec<u32>,
+    occupied: Vec<bool>,
+    occupied_neighbors: Vec<u8>,
+}
 
-        match seat {
-            Seat::Empty if total_occupied_adjacent_seats == 0 => Seat::Occupied,
-            Seat::Occupied if total_occupied_adjacent_seats >= 4 => Seat::Empty,
-            _ => seat,
+impl SeatGraph {
+    pub fn new(layout: &[Vec<Seat>], neighborhood: &Neighborhood<Seat>) -> Self {
+        let grid = Grid::new(layout);
+
+        let mut seat_ids = vec![u32::MAX; grid.cells.len()];
+        let mut occupied = Vec::new();
+        for (index, &cell) in grid.cells.iter().enumerate() {
+            if cell != Seat::Floor {
+                seat_ids[index] = occupied.len() as u32;
+                occupied.push(cell == Seat::Occupied);
+            }
+        }
+
+        let mut starts = Vec::with_capacity(occupied.len() + 1);
+        let mut neighbors = Vec::new();
+        let mut indices = Vec::with_capacity(DIRECTIONS.len());
+        for (index, &cell) in grid.cells.iter().enumerate() {
+            if cell == Seat::Floor {
+                continue;
+            }
+
+            let position = ((index / grid.cols) as isize, (index % grid.cols) as isize);
+            neighborhood.indices(&grid, position, &mut indices);
+
+            starts.push(neighbors.len());
+            neighbors.extend(
+                indices
+                    .iter()
+                    .map(|&i| seat_ids[i])
+                    .filter(|&id| id != u32::MAX),
+            );
         }
-    })
-}
-
-fn find_occupied_adjacent_for_direction(
-    layout: &Layout,
-    (row, column): (usize, usize),
-    (dr, dc): (isize, isize),
-) -> Option<Seat> {
-    let (mut row, mut column) = (row as isize, column as isize);
-
-    loop {
-        row += dr;
-        column += dc;
-
-        match layout
-            .get(row as usize)
-            .and_then(|row| row.get(column as usize))
-        {
-            Some(Seat::Floor) => continue,
-            Some(Seat::Occupied) => return Some(Seat::Occupied),
-            None | Some(Seat::Empty) => return None,
+        starts.push(neighbors.len());
+
+        let mut graph = SeatGraph {
+            starts,
+            neighbors,
+            occupied_neighbors: vec![0; occupied.len()],
+            occupied,
+        };
+        for seat in 0..graph.occupied.len() {
+            graph.occupied_neighbors[seat] = graph
+                .neighbors_of(seat)
+                .iter()
+                .filter(|&&n| graph.occupied[n as usize])
+                .count() as u8;
         }
+
+        graph
+    }
+
+    fn neighbors_of(&self, seat: usize) -> &[u32] {
+        &self.neighbors[self.starts[seat]..self.starts[seat + 1]]
+    }
+
+    // runs the seating rule until nothing changes and returns how many rounds it took
+    pub fn settle(&mut self, tolerance: u8) -> usize {
+        let seats = self.occupied.len();
+        let mut queue: Vec<u32> = (0..seats as u32).collect();
+        let mut queued = vec![false; seats];
+        let mut flips = Vec::new();
+        let mut generations = 0;
+
+        loop {
+            flips.clear();
+            for &seat in &queue {
+                let seat = seat as usize;
+                queued[seat] = false;
+
+                let flip = if self.occupied[seat] {
+                    self.occupied_neighbors[seat] >= tolerance
+                } else {
+                    self.occupied_neighbors[seat] == 0
+                };
+                if flip {
+                    flips.push(seat);
+                }
+            }
+
+            if flips.is_empty() {
+                return generations;
+            }
+            generations += 1;
+
+            queue.clear();
+            for &seat in &flips {
+                let occupied = !self.occupied[seat];
+                self.occupied[seat] = occupied;
+
+                for i in self.starts[seat]..self.starts[seat + 1] {
+                    let neighbor = self.neighbors[i] as usize;
+                    if occupied {
+                        self.occupied_neighbors[neighbor] += 1;
+                    } else {
+                        self.occupied_neighbors[neighbor] -= 1;
+                    }
+
+                    if !queued[neighbor] {
+                        queued[neighbor] = true;
+                        queue.push(neighbor as u32);
+                    }
+                }
+
+                if !queued[seat] {
+                    queued[seat] = true;
+                    queue.push(seat as u32);
+                }
+            }
+        }
+    }
+
+    pub fn count_occupied(&self) -> usize {
+        self.occupied.iter().filter(|&&o| o).count()
     }
 }
 
-#[aoc(day11, part2)]
-pub fn solve_part2(input_layout: &Layout) -> u32 {
-    find_final_seating(input_layout, |layout, seat, (row, column)| {
-        let total_occupied_adjacent_seats = DIRECTIONS
-            .iter()
-            .filter_map(|&(r, c)| {
-                find_occupied_adjacent_for_direction(&layout, (row, column), (r, c))
-            })
-            .count();
+type Layout = Vec<Vec<Seat>>;
+
+#[aoc_generator(day11)]
+pub fn generate_input(input: &str) -> Layout {
+    input
+        .lines()
+        .map(|l| l.chars().map(Seat::from).collect())
+        .collect()
+}
+
+fn seating_rule(tolerance: usize) -> impl Fn(Seat, &[Seat]) -> Seat {
+    move |seat, neighbors| {
+        let total_occupied_adjacent_seats =
+            neighbors.iter().filter(|&&s| s == Seat::Occupied).count();
 
         match seat {
             Seat::Empty if total_occupied_adjacent_seats == 0 => Seat::Occupied,
-            Seat::Occupied if total_occupied_adjacent_seats >= 5 => Seat::Empty,
+            Seat::Occupied if total_occupied_adjacent_seats >= tolerance => Seat::Empty,
             _ => seat,
         }
-    })
+    }
+}
+
+#[aoc(day11, part1)]
+pub fn solve_part1(input_layout: &[Vec<Seat>]) -> u32 {
+    let mut automaton = Automaton::new(input_layout, Neighborhood::Adjacent, seating_rule(4));
+    automaton.run();
+    automaton.count(Seat::Occupied) as u32
+}
+
+#[aoc(day11, part2)]
+pub fn solve_part2(input_layout: &[Vec<Seat>]) -> u32 {
+    let neighborhood = Neighborhood::LineOfSight(|seat| seat == Seat::Floor);
+    let mut graph = SeatGraph::new(input_layout, &neighborhood);
+    graph.settle(5);
+    graph.count_occupied() as u32
 }
 
 #[cfg(test)]
@@ -172,6 +445,110 @@ L.LLLLL.LL";
         assert_eq!(actual, 26);
     }
 
+    #[test]
+    fn example_generations() {
+        let input = get_input();
+
+        let mut automaton = Automaton::new(&input, Neighborhood::Adjacent, seating_rule(4));
+        let run = automaton.run();
+        assert_eq!(run.termination, Termination::FixedPoint);
+        assert_eq!(run.generations, 5);
+
+        let mut automaton = Automaton::new(
+            &input,
+            Neighborhood::LineOfSight(|seat| seat == Seat::Floor),
+            seating_rule(5),
+        );
+        assert_eq!(automaton.run().generations, 6);
+    }
+
+    #[test]
+    fn example_cycle() {
+        // a blinker oscillates between a horizontal and a vertical bar
+        let layout: Vec<Vec<bool>> = vec![
+            vec![false, false, false],
+            vec![true, true, true],
+            vec![false, false, false],
+        ];
+        let life = |alive, neighbors: &[bool]| {
+            let n = neighbors.iter().filter(|&&c| c).count();
+            n == 3 || (alive && n == 2)
+        };
+
+        let mut automaton = Automaton::new(&layout, Neighborhood::Adjacent, life);
+        let run = automaton.run();
+        assert_eq!(run.generations, 0);
+        assert_eq!(run.termination, Termination::Cycle { period: 2 });
+    }
+
+    #[test]
+    fn example_cycle_after_warm_up() {
+        // every cell counts 0, 1, 2, 3, 4 and then loops back to 2
+        let layout = vec![vec![0u8, 3]];
+        let mut automaton = Automaton::new(&layout, Neighborhood::Custom(vec![]), |c, _: &[u8]| {
+            if c == 4 {
+                2
+            } else {
+                c + 1
+            }
+        });
+
+        let run = automaton.run();
+        assert_eq!(run.generations, 2);
+        assert_eq!(run.termination, Termination::Cycle { period: 3 });
+        assert_eq!(automaton.grid.cells, vec![2, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "every row of the layout needs the same number of cells")]
+    fn example_ragged_layout() {
+        let input = generate_input("L.L\nL\n");
+        SeatGraph::new(&input, &Neighborhood::Adjacent);
+    }
+
+    #[test]
+    fn example_custom_neighborhood() {
+        // with only the cell to the left as neighbor, a single live cell travels right
+        let layout = vec![vec![true, false, false, false]];
+        let neighborhood = Neighborhood::Custom(vec![(0, -1)]);
+        let mut automaton = Automaton::new(&layout, neighborhood, |_, n: &[bool]| n == [true]);
+
+        automaton.step();
+        assert_eq!(automaton.grid.cells, vec![false, true, false, false]);
+
+        let run = automaton.run();
+        assert_eq!(run.generations, 3);
+        assert_eq!(run.termination, Termination::FixedPoint);
+        assert_eq!(automaton.count(true), 0);
+    }
+
+    #[test]
+    fn example_seat_graph() {
+        let input = get_input();
+
+        let mut graph = SeatGraph::new(&input, &Neighborhood::Adjacent);
+        assert_eq!(graph.settle(4), 5);
+        assert_eq!(graph.count_occupied(), 37);
+
+        let mut graph = SeatGraph::new(&input, &Neighborhood::LineOfSight(|s| s == Seat::Floor));
+        assert_eq!(graph.neighbors_of(0), &[7, 8, 1]);
+        assert_eq!(graph.settle(5), 6);
+        assert_eq!(graph.count_occupied(), 26);
+    }
+
+    #[test]
+    fn test_input_seat_graph_matches_automaton() {
+        let text = fs::read_to_string("input/2020/day11.txt").unwrap();
+        let input = generate_input(&text);
+
+        let neighborhood = Neighborhood::LineOfSight(|seat| seat == Seat::Floor);
+        let mut graph = SeatGraph::new(&input, &neighborhood);
+        let mut automaton = Automaton::new(&input, neighborhood, seating_rule(5));
+
+        assert_eq!(graph.settle(5), automaton.run().generations);
+        assert_eq!(graph.count_occupied(), automaton.count(Seat::Occupied));
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2020/day11.txt").unwrap();
@@ -1,3 +1,6 @@
+use std::fs;
+use std::io;
+use std::path::Path;
 use std::str::FromStr;
 
 pub enum Instruction {
@@ -29,10 +32,27 @@ impl FromStr for Instruction {
     }
 }
 
-struct Ferry {
+#[derive(Debug, PartialEq)]
+pub enum NavigationError {
+    UnsupportedAngle(i32),
+}
+
+// rotates counter-clockwise by a multiple of 90 degrees
+fn rotate((x, y): (i32, i32), degrees: i32) -> Result<(i32, i32), NavigationError> {
+    match degrees.rem_euclid(360) {
+        0 => Ok((x, y)),
+        90 => Ok((-y, x)),
+        180 => Ok((-x, -y)),
+        270 => Ok((y, -x)),
+        _ => Err(NavigationError::UnsupportedAngle(degrees)),
+    }
+}
+
+pub struct Ferry {
     x: i32,
     y: i32,
-    angle: i32,
+    heading: (i32, i32),
+    path: Vec<(i32, i32)>,
 }
 
 impl Ferry {
@@ -40,39 +60,123 @@ impl Ferry {
         Self {
             x: 0,
             y: 0,
-            angle: 0,
+            heading: (1, 0),
+            path: vec![(0, 0)],
         }
     }
 
-    fn forward(&mut self, d: &i32) {
-        let radians = (self.angle as f32).to_radians();
-
-        let d = *d as f32;
-
-        self.x += (d * radians.cos()).round() as i32;
-        self.y += (d * radians.sin()).round() as i32;
+    fn move_by(&mut self, dx: i32, dy: i32) {
+        self.x += dx;
+        self.y += dy;
+        self.path.push((self.x, self.y));
     }
 
-    fn to_waypoint(&mut self, waypoint: &Waypoint, amount: &i32) {
-        self.x += waypoint.x * amount;
-        self.y += waypoint.y * amount;
+    fn move_to_waypoint(&mut self, waypoint: &Waypoint, amount: &i32) {
+        self.move_by(waypoint.x * amount, waypoint.y * amount);
     }
 
     fn manhattan_distance(&self) -> u32 {
         (self.x.abs() + self.y.abs()) as u32
     }
 
-    fn act(&mut self, instruction: &Instruction) {
+    fn act(&mut self, instruction: &Instruction) -> Result<(), NavigationError> {
         match instruction {
-            Instruction::North(amount) => self.y += amount,
-            Instruction::South(amount) => self.y -= amount,
-            Instruction::East(amount) => self.x += amount,
-            Instruction::West(amount) => self.x -= amount,
-            Instruction::Left(amount) => self.angle += amount,
-            Instruction::Right(amount) => self.angle -= amount,
-            Instruction::Forward(amount) => self.forward(amount),
+            Instruction::North(amount) => self.move_by(0, *amount),
+            Instruction::South(amount) => self.move_by(0, -amount),
+            Instruction::East(amount) => self.move_by(*amount, 0),
+            Instruction::West(amount) => self.move_by(-amount, 0),
+            Instruction::Left(amount) => self.heading = rotate(self.heading, *amount)?,
+            Instruction::Right(amount) => self.heading = rotate(self.heading, -amount)?,
+            Instruction::Forward(amount) => {
+                self.move_by(self.heading.0 * amount, self.heading.1 * amount)
+            }
         }
+
+        Ok(())
     }
+
+    pub fn path(&self) -> &[(i32, i32)] {
+        &self.path
+    }
+
+    // smallest and largest corner of the area the ferry has been in
+    pub fn bounding_box(&self) -> ((i32, i32), (i32, i32)) {
+        bounding_box(&self.path)
+    }
+
+    // manhattan length of the whole route
+    pub fn total_distance(&self) -> u32 {
+        self.path
+            .windows(2)
+            .map(|w| ((w[1].0 - w[0].0).abs() + (w[1].1 - w[0].1).abs()) as u32)
+            .sum()
+    }
+
+    pub fn to_svg(&self, waypoint_trail: Option<&[(i32, i32)]>) -> String {
+        let mut points = self.path.clone();
+        points.extend(waypoint_trail.unwrap_or(&[]));
+
+        let ((min_x, min_y), (max_x, max_y)) = bounding_box(&points);
+        let margin = ((max_x - min_x).max(max_y - min_y) / 20).max(1);
+        let stroke = margin as f32 / 5.0;
+
+        // svg's y axis points down, so north is flipped to the top
+        let polyline = |points: &[(i32, i32)], color: &str, extra: &str| {
+            let points: Vec<String> = points
+                .iter()
+                .map(|(x, y)| format!("{},{}", x, -y))
+                .collect();
+            format!(
+                "  <polyline fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"{} points=\"{}\"/>\n",
+                color,
+                stroke,
+                extra,
+                points.join(" ")
+            )
+        };
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+            min_x - margin,
+            -max_y - margin,
+            max_x - min_x + 2 * margin,
+            max_y - min_y + 2 * margin
+        );
+        if let Some(trail) = waypoint_trail {
+            svg += &polyline(trail, "orange", " stroke-dasharray=\"4\"");
+        }
+        svg += &polyline(&self.path, "navy", "");
+        svg += &format!(
+            "  <circle cx=\"0\" cy=\"0\" r=\"{}\" fill=\"green\"/>\n",
+            stroke * 3.0
+        );
+        svg += &format!(
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"red\"/>\n",
+            self.x,
+            -self.y,
+            stroke * 3.0
+        );
+        svg += "</svg>\n";
+
+        svg
+    }
+
+    pub fn export_svg<P: AsRef<Path>>(
+        &self,
+        path: P,
+        waypoint_trail: Option<&[(i32, i32)]>,
+    ) -> io::Result<()> {
+        fs::write(path, self.to_svg(waypoint_trail))
+    }
+}
+
+fn bounding_box(points: &[(i32, i32)]) -> ((i32, i32), (i32, i32)) {
+    points.iter().fold(
+        ((i32::MAX, i32::MAX), (i32::MIN, i32::MIN)),
+        |((min_x, min_y), (max_x, max_y)), &(x, y)| {
+            ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
+        },
+    )
 }
 
 #[aoc_generator(day12)]
@@ -80,12 +184,20 @@ pub fn generate_input(input: &str) -> Vec<Instruction> {
     input.lines().map(|l| l.parse().unwrap()).collect()
 }
 
-#[aoc(day12, part1)]
-pub fn solve_part1(instructions: &Vec<Instruction>) -> u32 {
+pub fn navigate(instructions: &[Instruction]) -> Result<Ferry, NavigationError> {
     let mut ferry = Ferry::new();
-    instructions.iter().for_each(|i| ferry.act(i));
+    for instruction in instructions {
+        ferry.act(instruction)?;
+    }
+
+    Ok(ferry)
+}
 
-    ferry.manhattan_distance()
+#[aoc(day12, part1)]
+pub fn solve_part1(instructions: &[Instruction]) -> u32 {
+    navigate(instructions)
+        .expect("ferry can only turn by multiples of 90 degrees")
+        .manhattan_distance()
 }
 
 struct Waypoint {
@@ -98,48 +210,54 @@ impl Waypoint {
         Self { x, y }
     }
 
-    fn turn(&mut self, angles: i32) {
-        let radians = (angles as f32).to_radians();
-
-        let x = self.x as f32;
-        let y = self.y as f32;
-
-        let sin = radians.sin();
-        let cos = radians.cos();
-
-        // 𝐶′𝑥=𝐵′𝑥cos𝛼−𝐵′𝑦sin𝛼 and 𝐶′𝑦=𝐵′𝑥sin𝛼+𝐵′𝑦cos𝛼
-        let new_x = (x * cos - y * sin).round() as i32;
-        let new_y = (x * sin + y * cos).round() as i32;
+    fn turn(&mut self, angles: i32) -> Result<(), NavigationError> {
+        let (x, y) = rotate((self.x, self.y), angles)?;
+        self.x = x;
+        self.y = y;
 
-        self.x = new_x;
-        self.y = new_y;
+        Ok(())
     }
 
-    fn act(&mut self, instruction: &Instruction) {
+    fn act(&mut self, instruction: &Instruction) -> Result<(), NavigationError> {
         match instruction {
             Instruction::North(amount) => self.y += amount,
             Instruction::South(amount) => self.y -= amount,
             Instruction::East(amount) => self.x += amount,
             Instruction::West(amount) => self.x -= amount,
-            Instruction::Left(amount) => self.turn(*amount),
-            Instruction::Right(amount) => self.turn(-amount),
+            Instruction::Left(amount) => self.turn(*amount)?,
+            Instruction::Right(amount) => self.turn(-amount)?,
             _ => (),
         }
+
+        Ok(())
     }
 }
 
-#[aoc(day12, part2)]
-pub fn solve_part2(instructions: &Vec<Instruction>) -> u32 {
+// returns the ferry and the absolute positions the waypoint was at after each instruction
+pub fn navigate_with_waypoint(
+    instructions: &[Instruction],
+) -> Result<(Ferry, Vec<(i32, i32)>), NavigationError> {
     let mut waypoint = Waypoint::new(10, 1);
     let mut ferry = Ferry::new();
-    instructions
-        .iter()
-        .for_each(|instruction| match instruction {
-            Instruction::Forward(amount) => ferry.to_waypoint(&waypoint, amount),
-            _ => waypoint.act(instruction),
-        });
-
-    ferry.manhattan_distance()
+    let mut trail = vec![(waypoint.x, waypoint.y)];
+
+    for instruction in instructions {
+        match instruction {
+            Instruction::Forward(amount) => ferry.move_to_waypoint(&waypoint, amount),
+            _ => waypoint.act(instruction)?,
+        }
+        trail.push((ferry.x + waypoint.x, ferry.y + waypoint.y));
+    }
+
+    Ok((ferry, trail))
+}
+
+#[aoc(day12, part2)]
+pub fn solve_part2(instructions: &[Instruction]) -> u32 {
+    navigate_with_waypoint(instructions)
+        .expect("waypoint can only rotate by multiples of 90 degrees")
+        .0
+        .manhattan_distance()
 }
 
 #[cfg(test)]
@@ -172,6 +290,54 @@ F11
         assert_eq!(actual, 286);
     }
 
+    #[test]
+    fn example_path() {
+        let input = get_input();
+
+        let ferry = navigate(&input).unwrap();
+        assert_eq!(ferry.path(), &[(0, 0), (10, 0), (10, 3), (17, 3), (17, -8)]);
+        assert_eq!(ferry.bounding_box(), ((0, -8), (17, 3)));
+        assert_eq!(ferry.total_distance(), 31);
+
+        let (ferry, trail) = navigate_with_waypoint(&input).unwrap();
+        assert_eq!(ferry.path().last(), Some(&(214, -72)));
+        assert_eq!(ferry.bounding_box(), ((0, -72), (214, 38)));
+        assert_eq!(trail.last(), Some(&(218, -82)));
+    }
+
+    #[test]
+    fn example_unsupported_angle() {
+        let input = generate_input("F10\nL45\nF10\n");
+        assert_eq!(
+            navigate(&input).err(),
+            Some(NavigationError::UnsupportedAngle(45))
+        );
+        assert_eq!(
+            navigate_with_waypoint(&input).err(),
+            Some(NavigationError::UnsupportedAngle(45))
+        );
+
+        let input = generate_input("R450\nF1\nL-270\nF1\n");
+        let ferry = navigate(&input).unwrap();
+        assert_eq!(ferry.path(), &[(0, 0), (0, -1), (1, -1)]);
+    }
+
+    #[test]
+    fn example_svg() {
+        let input = get_input();
+        let (ferry, trail) = navigate_with_waypoint(&input).unwrap();
+
+        let svg = ferry.to_svg(Some(&trail));
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("points=\"0,0 100,-10 170,-38 214,72\""));
+        assert!(svg.contains("points=\"10,-1 110,-11 110,-14 180,-42 174,-28 218,82\""));
+
+        let path = std::env::temp_dir().join("aoc_2020_day12_example.svg");
+        ferry.export_svg(&path, Some(&trail)).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), svg);
+        fs::remove_file(path).unwrap();
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2020/day12.txt").unwrap();
@@ -37,7 +37,6 @@ pub fn generate_input_part2(input: &str) -> Vec<BusLine> {
 
 #[aoc(day13, part1)]
 pub fn solve_part1((timestamp, bus_lines): &(u64, Vec<u64>)) -> u64 {
-    let bus_lines = bus_lines;
     let mut t = 0;
     loop {
         let earliest = bus_lines
@@ -14,7 +14,7 @@ fn find_last_spoken_number(numbers: &[i64], target_turn: usize) -> i64 {
         .map(|(i, &n)| (n, 1 + i as i64))
         .collect();
 
-    let mut current_number = numbers.last().unwrap().clone();
+    let mut current_number = *numbers.last().unwrap();
 
     for turn in len..target_turn {
         current_number = match last_spokens.entry(current_number) {
@@ -55,7 +55,7 @@ impl FromStr for Ticket {
 }
 
 impl Ticket {
-    fn sum_invalid_fields(&self, fields: &Vec<TicketFieldRule>) -> Option<u32> {
+    fn sum_invalid_fields(&self, fields: &[TicketFieldRule]) -> Option<u32> {
         let invalid: Vec<&u32> = self
             .0
             .iter()
@@ -113,7 +113,11 @@ pub fn solve_part2(train: &Train) -> u64 {
     let valid_nearby_tickets: Vec<&Ticket> = train
         .nearby_tickets
         .iter()
-        .filter(|&ticket| ticket.sum_invalid_fields(&train.ticket_field_rules) == None)
+        .filter(|&ticket| {
+            ticket
+                .sum_invalid_fields(&train.ticket_field_rules)
+                .is_none()
+        })
         .collect();
 
     let mut departure_field_product = 1u64;
@@ -1,177 +1,218 @@
-#[derive(Clone, Copy)]
-pub enum Cell {
-    Inactive = 0,
-    Active = 1,
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+pub type Point<const N: usize> = [i32; N];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rule {
+    birth: Vec<usize>,
+    survival: Vec<usize>,
+}
+
+impl Rule {
+    fn next_state(&self, active: bool, neighbors: usize) -> bool {
+        if active {
+            self.survival.contains(&neighbors)
+        } else {
+            self.birth.contains(&neighbors)
+        }
+    }
 }
 
-struct Grid {
-    size: u8,
-    dimension: u8,
-    cells: Vec<Cell>,
+impl Default for Rule {
+    fn default() -> Self {
+        Rule {
+            birth: vec![3],
+            survival: vec![2, 3],
+        }
+    }
 }
 
-impl Grid {
-    fn new(input: &[Cell], n: u8, dimension: u8) -> Grid {
-        assert!(dimension == 3 || dimension == 4);
+impl FromStr for Rule {
+    type Err = ();
 
-        let size = 18;
-        let cells = (0..i32::pow(size as i32, dimension as u32))
-            .map(|_| Cell::Inactive)
-            .collect();
-        let mut grid = Grid {
-            size,
-            cells,
-            dimension,
+    // parses the usual life notation, e.g. "B3/S23"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (birth, survival) = s.split_once('/').ok_or(())?;
+
+        let parse_counts = |part: &str, prefix: char| -> Result<Vec<usize>, ()> {
+            part.strip_prefix(prefix)
+                .ok_or(())?
+                .chars()
+                .map(|c| c.to_digit(10).map(|d| d as usize).ok_or(()))
+                .collect()
         };
 
-        for y in 0..n {
-            for x in 0..n {
-                let index_input = (x as u16 + n as u16 * y as u16) as usize;
-                let offset = (size - n) / 2;
-                let index_grid =
-                    grid.get_index(offset + x as u8, offset + y as u8, size / 2, Some(size / 2));
+        Ok(Rule {
+            birth: parse_counts(birth, 'B')?,
+            survival: parse_counts(survival, 'S')?,
+        })
+    }
+}
 
-                grid.cells[index_grid] = input[index_input];
-            }
-        }
+pub struct Grid<const N: usize> {
+    active: HashSet<Point<N>>,
+    offsets: Vec<Point<N>>,
+    rule: Rule,
+    reduced: bool,
+}
 
-        grid
+impl<const N: usize> Grid<N> {
+    pub fn new(slice: &[(i32, i32)], rule: Rule) -> Grid<N> {
+        assert!(N >= 2, "a grid needs at least two dimensions");
+
+        let active = slice
+            .iter()
+            .map(|&(x, y)| {
+                let mut point = [0; N];
+                point[0] = x;
+                point[1] = y;
+                point
+            })
+            .collect();
+
+        Grid {
+            active,
+            offsets: neighbor_offsets(),
+            rule,
+            reduced: false,
+        }
     }
 
-    fn out_of_bounds(&self, i: i8) -> bool {
-        i.is_negative() || i as u8 >= self.size
+    // The starting slice is flat, so every dimension after the first two stays
+    // mirror-symmetric around zero. A reduced grid only keeps the cells whose
+    // extra coordinates are all non-negative and accounts for their mirror
+    // images when counting neighbors and active cells.
+    pub fn new_reduced(slice: &[(i32, i32)], rule: Rule) -> Grid<N> {
+        Grid {
+            reduced: true,
+            ..Grid::new(slice, rule)
+        }
     }
 
-    fn run_six_cycles(&mut self) {
-        for _ in 0..6 {
+    pub fn run_cycles(&mut self, cycles: usize) {
+        for _ in 0..cycles {
             self.run_cycle();
         }
     }
 
-    fn run_cycle(&mut self) {
-        let mut next = self.cells.clone();
-
-        for z in 0..self.size {
-            for y in 0..self.size {
-                for x in 0..self.size {
-                    for w in 0..self.size {
-                        let index = self.get_index(x, y, z, Some(w));
-                        let cell = self.cells[index];
-                        let occupied_neighbors = self.count_occupied_neighbors(x, y, z, w);
-
-                        let next_cell = match (cell, occupied_neighbors) {
-                            (Cell::Active, 2..=3) => Cell::Active,
-                            (Cell::Active, _) => Cell::Inactive,
-
-                            (Cell::Inactive, 3) => Cell::Active,
-                            (Cell::Inactive, _) => Cell::Inactive,
-                        };
+    pub fn run_cycle(&mut self) {
+        let mut neighbors: HashMap<Point<N>, usize> = HashMap::new();
 
-                        next[index] = next_cell;
+        for cell in &self.active {
+            for image in self.images(cell) {
+                for offset in &self.offsets {
+                    let neighbor = add(&image, offset);
+                    if self.reduced && neighbor[2..].iter().any(|&d| d < 0) {
+                        continue;
                     }
+                    *neighbors.entry(neighbor).or_insert(0) += 1;
                 }
             }
         }
 
-        self.cells = next;
-    }
+        let mut next: HashSet<Point<N>> = neighbors
+            .iter()
+            .filter(|(cell, &count)| self.rule.next_state(self.active.contains(*cell), count))
+            .map(|(&cell, _)| cell)
+            .collect();
 
-    fn get_index(&self, x: u8, y: u8, z: u8, w: Option<u8>) -> usize {
-        let w = match self.dimension {
-            3 => 0,
-            4 => w.unwrap(),
-            _ => unreachable!(),
-        } as usize;
-        let size = self.size as usize;
-        x as usize + (size * (y as usize + (size * (z as usize + (size * w)))))
-    }
+        // cells without any active neighbor are never visited above
+        if self.rule.survival.contains(&0) {
+            next.extend(
+                self.active
+                    .iter()
+                    .filter(|cell| !neighbors.contains_key(*cell))
+                    .copied(),
+            );
+        }
 
-    fn count_occupied_neighbors(&self, x: u8, y: u8, z: u8, w: u8) -> u8 {
-        let mut count = 0;
+        self.active = next;
+    }
 
-        for dz in -1..=1 {
-            let nz = z as i8 + dz;
-            if self.out_of_bounds(nz) {
-                continue;
-            }
+    pub fn count_active(&self) -> usize {
+        if !self.reduced {
+            return self.active.len();
+        }
 
-            for dy in -1..=1 {
-                let ny = y as i8 + dy;
-                if self.out_of_bounds(ny) {
-                    continue;
-                }
+        self.active
+            .iter()
+            .map(|cell| 1 << cell[2..].iter().filter(|&&d| d != 0).count())
+            .sum()
+    }
 
-                for dx in -1..=1 {
-                    let nx = x as i8 + dx;
-                    if self.out_of_bounds(nx) {
-                        continue;
-                    }
+    // Mirror images of a reduced cell that can be adjacent to another reduced
+    // cell: only coordinates equal to 1 have a reflection (-1) within reach.
+    fn images(&self, cell: &Point<N>) -> Vec<Point<N>> {
+        let mut images = vec![*cell];
+        if !self.reduced {
+            return images;
+        }
 
-                    let nx = nx as u8;
-                    let ny = ny as u8;
-                    let nz = nz as u8;
-
-                    if self.dimension == 3 {
-                        if dx == 0 && dy == 0 && dz == 0 {
-                            continue;
-                        }
-
-                        let index = self.get_index(nx, ny, nz, None);
-                        count += self.cells[index] as u8;
-                    } else {
-                        for dw in -1..=1 {
-                            if dx == 0 && dy == 0 && dz == 0 && dw == 0 {
-                                continue;
-                            }
-
-                            let nw = w as i8 + dw;
-                            if self.out_of_bounds(nw) {
-                                continue;
-                            }
-
-                            let index = self.get_index(nx, ny, nz, Some(nw as u8));
-                            count += self.cells[index] as u8;
-                        }
-                    }
+        for d in 2..N {
+            if cell[d] == 1 {
+                for i in 0..images.len() {
+                    let mut image = images[i];
+                    image[d] = -1;
+                    images.push(image);
                 }
             }
         }
 
-        count
+        images
     }
+}
 
-    fn count_active(&self) -> u16 {
-        self.cells.iter().map(|&cell| cell as u16).sum()
+fn add<const N: usize>(a: &Point<N>, b: &Point<N>) -> Point<N> {
+    let mut sum = *a;
+    for (s, d) in sum.iter_mut().zip(b.iter()) {
+        *s += d;
     }
+    sum
+}
+
+fn neighbor_offsets<const N: usize>() -> Vec<Point<N>> {
+    (0..3_usize.pow(N as u32))
+        .map(|mut n| {
+            let mut offset = [0; N];
+            for d in offset.iter_mut() {
+                *d = (n % 3) as i32 - 1;
+                n /= 3;
+            }
+            offset
+        })
+        .filter(|offset| offset.iter().any(|&d| d != 0))
+        .collect()
 }
 
 #[aoc_generator(day17)]
-pub fn generate_input(input: &str) -> (u8, Vec<Cell>) {
-    (
-        input.lines().count() as u8,
-        input
-            .replace("\n", "")
-            .chars()
-            .map(|c| match c {
-                '#' => Cell::Active,
-                _ => Cell::Inactive,
-            })
-            .collect::<Vec<Cell>>(),
-    )
+pub fn generate_input(input: &str) -> Vec<(i32, i32)> {
+    input
+        .lines()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|&(_, c)| c == '#')
+                .map(move |(x, _)| (x as i32, y as i32))
+        })
+        .collect()
 }
 
-#[aoc(day17, part1)]
-pub fn solve_part1((n, input): &(u8, Vec<Cell>)) -> u16 {
-    let mut grid = Grid::new(input, *n, 3);
-    grid.run_six_cycles();
+pub fn count_active_after<const N: usize>(input: &[(i32, i32)], cycles: usize) -> usize {
+    let mut grid = Grid::<N>::new_reduced(input, Rule::default());
+    grid.run_cycles(cycles);
     grid.count_active()
 }
 
+#[aoc(day17, part1)]
+pub fn solve_part1(input: &[(i32, i32)]) -> usize {
+    count_active_after::<3>(input, 6)
+}
+
 #[aoc(day17, part2)]
-pub fn solve_part2((n, input): &(u8, Vec<Cell>)) -> u16 {
-    let mut grid = Grid::new(input, *n, 4);
-    grid.run_six_cycles();
-    grid.count_active()
+pub fn solve_part2(input: &[(i32, i32)]) -> usize {
+    count_active_after::<4>(input, 6)
 }
 
 #[cfg(test)]
@@ -179,7 +220,7 @@ mod tests {
     use super::*;
     use std::fs;
 
-    fn get_input() -> (u8, Vec<Cell>) {
+    fn get_input() -> Vec<(i32, i32)> {
         let text = ".#.
 ..#
 ###
@@ -201,6 +242,63 @@ mod tests {
         assert_eq!(actual, 848);
     }
 
+    #[test]
+    fn example_unbounded_growth() {
+        // a glider on a 2d plane keeps moving well past any fixed bounds
+        let input = get_input();
+        let mut grid = Grid::<2>::new(&input, Rule::default());
+        grid.run_cycles(100);
+        assert_eq!(grid.count_active(), 5);
+        assert!(grid.active.iter().all(|&[x, y]| x >= 25 && y >= 25));
+    }
+
+    #[test]
+    fn example_custom_rule() {
+        let rule: Rule = "B36/S23".parse().unwrap();
+        assert_eq!(rule.birth, vec![3, 6]);
+        assert_eq!(rule.survival, vec![2, 3]);
+        assert_eq!("B3S23".parse::<Rule>(), Err(()));
+
+        // under B1/S nothing survives and every cell touching an active one is born
+        let mut grid = Grid::<3>::new(&[(0, 0)], "B1/S".parse().unwrap());
+        grid.run_cycle();
+        assert_eq!(grid.count_active(), 26);
+    }
+
+    fn assert_reduced_matches_full<const N: usize>(input: &[(i32, i32)]) {
+        let mut full = Grid::<N>::new(input, Rule::default());
+        let mut reduced = Grid::<N>::new_reduced(input, Rule::default());
+
+        for _ in 0..6 {
+            full.run_cycle();
+            reduced.run_cycle();
+            assert_eq!(full.count_active(), reduced.count_active());
+        }
+    }
+
+    #[test]
+    fn example_reduced_matches_full() {
+        let input = get_input();
+        assert_reduced_matches_full::<3>(&input);
+        assert_reduced_matches_full::<4>(&input);
+    }
+
+    #[test]
+    fn test_input_reduced_matches_full() {
+        let text = fs::read_to_string("input/2020/day17.txt").unwrap();
+        let input = generate_input(&text);
+        assert_reduced_matches_full::<3>(&input);
+        assert_reduced_matches_full::<4>(&input);
+    }
+
+    #[test]
+    fn example_higher_dimensions() {
+        let input = get_input();
+        assert_eq!(count_active_after::<5>(&input, 6), 5760);
+        // six cycles in 6D take seconds without optimisations
+        assert_eq!(count_active_after::<6>(&input, 3), 15744);
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2020/day17.txt").unwrap();
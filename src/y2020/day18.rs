@@ -11,15 +11,10 @@ fn precedence_part1(_s: &str, _t: &str) -> bool {
 }
 
 fn precedence_part2(s: &str, t: &str) -> bool {
-    match (s, t) {
-        ("+", "*") => true,
-        ("*", "*") => true,
-        ("+", "+") => true,
-        _ => false,
-    }
+    matches!((s, t), ("+", "*") | ("*", "*") | ("+", "+"))
 }
 
-fn infix_to_postfix<F>(infix: &Vec<String>, precedence: F) -> Vec<String>
+fn infix_to_postfix<F>(infix: &[String], precedence: F) -> Vec<String>
 where
     F: Fn(&str, &str) -> bool,
 {
@@ -36,9 +31,7 @@ where
                 postfix.push(item);
             }
             o if is_operator(o) => {
-                let x = stack.pop();
-                if x.is_some() {
-                    let mut y = x.unwrap();
+                if let Some(mut y) = stack.pop() {
                     while is_operator(y) && precedence(y, item) {
                         postfix.push(y);
                         y = stack.pop().unwrap();
@@ -89,15 +82,15 @@ pub fn generate_input_part1(input: &str) -> Vec<Vec<String>> {
         .lines()
         .map(|l: &str| {
             let l = l.replace("(", " ( ").replace(")", " ) ").replace("  ", " ");
-            let tokens = l.split_whitespace().map(|l| l.to_string()).collect();
+            let tokens: Vec<String> = l.split_whitespace().map(|l| l.to_string()).collect();
             infix_to_postfix(&tokens, precedence_part1)
         })
         .collect()
 }
 
 #[aoc(day18, part1)]
-pub fn solve_part1(input: &Vec<Vec<String>>) -> u64 {
-    input.iter().map(|postfix| evaluate_postfix(postfix)).sum()
+pub fn solve_part1(input: &[Vec<String>]) -> u64 {
+    input.iter().map(evaluate_postfix).sum()
 }
 
 #[aoc_generator(day18, part2)]
@@ -106,15 +99,15 @@ pub fn generate_input_part2(input: &str) -> Vec<Vec<String>> {
         .lines()
         .map(|l: &str| {
             let l = l.replace("(", " ( ").replace(")", " ) ").replace("  ", " ");
-            let tokens = l.split_whitespace().map(|l| l.to_string()).collect();
+            let tokens: Vec<String> = l.split_whitespace().map(|l| l.to_string()).collect();
             infix_to_postfix(&tokens, precedence_part2)
         })
         .collect()
 }
 
 #[aoc(day18, part2)]
-pub fn solve_part2(input: &Vec<Vec<String>>) -> u64 {
-    input.iter().map(|postfix| evaluate_postfix(postfix)).sum()
+pub fn solve_part2(input: &[Vec<String>]) -> u64 {
+    input.iter().map(evaluate_postfix).sum()
 }
 
 #[cfg(test)]
@@ -76,7 +76,7 @@ fn matches<'a>(chars: &'a [char], index: &usize, rules: &Rules) -> Option<Vec<&'
                     for rule_ind in subrule {
                         let mut next_subrule_results = subrule_results
                             .iter()
-                            .filter_map(|r| matches(&r, &rule_ind, rules))
+                            .filter_map(|r| matches(r, rule_ind, rules))
                             .peekable();
                         if next_subrule_results.peek().is_some() {
                             subrule_results = next_subrule_results.flatten().collect();
@@ -102,7 +102,7 @@ pub fn solve_part1((rules, messages): &(Rules, Vec<String>)) -> usize {
         .par_iter()
         .filter(|message| {
             let chars: Vec<_> = message.chars().collect();
-            matches(&chars, &0, &rules)
+            matches(&chars, &0, rules)
                 .map(|results| results.iter().any(|r| r.is_empty()))
                 .unwrap_or(false)
         })
@@ -1,29 +1,110 @@
-use itertools::Itertools;
-use std::collections::HashSet;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+#[derive(Debug, PartialEq)]
+pub struct ElfStats {
+    pub items: usize,
+    pub total: u32,
+    pub mean: f64,
+    pub max_item: Option<u32>,
+}
+
+// Every elf's food items, in the order they were listed.
+#[derive(Debug)]
+pub struct Inventory {
+    elves: Vec<Vec<u32>>,
+}
+
+impl Inventory {
+    pub fn new(elves: Vec<Vec<u32>>) -> Self {
+        Inventory { elves }
+    }
+
+    pub fn len(&self) -> usize {
+        self.elves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elves.is_empty()
+    }
+
+    pub fn items(&self, elf: usize) -> &[u32] {
+        &self.elves[elf]
+    }
+
+    pub fn total(&self, elf: usize) -> u32 {
+        self.elves[elf].iter().sum()
+    }
+
+    pub fn stats(&self, elf: usize) -> ElfStats {
+        let items = self.items(elf);
+        let total = self.total(elf);
+
+        ElfStats {
+            items: items.len(),
+            total,
+            mean: if items.is_empty() {
+                0.0
+            } else {
+                total as f64 / items.len() as f64
+            },
+            max_item: items.iter().copied().max(),
+        }
+    }
+
+    // The `k` elves carrying the most as (elf, total), most first. Elves
+    // with equal totals keep their input order.
+    pub fn top_k(&self, k: usize) -> Vec<(usize, u32)> {
+        // a min-heap of the best elves so far, the weakest one on top
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        for elf in 0..self.len() {
+            best.push(Reverse((self.total(elf), Reverse(elf))));
+            if best.len() > k {
+                best.pop();
+            }
+        }
+
+        best.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(elf)))| (elf, total))
+            .collect()
+    }
+
+    // like `top_k`, but also every other elf tied with the last one
+    pub fn top_k_with_ties(&self, k: usize) -> Vec<(usize, u32)> {
+        let mut top = self.top_k(k);
+
+        if let Some(&(_, threshold)) = top.last() {
+            let tied = (0..self.len())
+                .filter(|&elf| self.total(elf) == threshold)
+                .filter(|elf| !top.iter().any(|(chosen, _)| chosen == elf))
+                .map(|elf| (elf, threshold))
+                .collect::<Vec<_>>();
+            top.extend(tied);
+        }
+
+        top
+    }
+}
 
 #[aoc_generator(day01)]
-pub fn generate_input(input: &str) -> HashSet<u32> {
-    input
-        .split("\n\n")
-        .map(|elf| {
-            elf.lines()
-                .map(|food| {
-                    let amount: u32 = food.parse().unwrap();
-                    amount
-                })
-                .sum()
-        })
-        .collect()
+pub fn generate_input(input: &str) -> Inventory {
+    Inventory::new(
+        input
+            .split("\n\n")
+            .map(|elf| elf.lines().map(|food| food.parse().unwrap()).collect())
+            .collect(),
+    )
 }
 
 #[aoc(day01, part1)]
-pub fn solve_part1(input: &HashSet<u32>) -> u32 {
-    *input.into_iter().max().unwrap()
+pub fn solve_part1(input: &Inventory) -> u32 {
+    input.top_k(1).iter().map(|&(_, total)| total).sum()
 }
 
 #[aoc(day01, part2)]
-pub fn solve_part2(input: &HashSet<u32>) -> u32 {
-    input.into_iter().sorted_by(|a, b| b.cmp(a)).take(3).sum()
+pub fn solve_part2(input: &Inventory) -> u32 {
+    input.top_k(3).iter().map(|&(_, total)| total).sum()
 }
 
 #[cfg(test)]
@@ -31,7 +112,7 @@ mod tests {
     use super::*;
     use std::fs;
 
-    fn get_input() -> HashSet<u32> {
+    fn get_input() -> Inventory {
         let text = "1000
 2000
 3000
@@ -62,6 +143,40 @@ mod tests {
         let input = get_input();
 
         assert_eq!(solve_part2(&input), 45000);
+        assert_eq!(input.top_k(3), vec![(3, 24000), (2, 11000), (4, 10000)]);
+    }
+
+    #[test]
+    fn example_stats() {
+        let input = get_input();
+
+        assert_eq!(input.items(2), &[5000, 6000]);
+        assert_eq!(
+            input.stats(3),
+            ElfStats {
+                items: 3,
+                total: 24000,
+                mean: 8000.0,
+                max_item: Some(9000)
+            }
+        );
+        assert_eq!(input.stats(1).max_item, Some(4000));
+    }
+
+    #[test]
+    fn example_equal_totals() {
+        let input = generate_input("6000\n\n1000\n5000\n\n3000\n\n2000\n4000\n\n6000\n");
+
+        assert_eq!(solve_part1(&input), 6000);
+        assert_eq!(solve_part2(&input), 18000);
+        assert_eq!(input.top_k(2), vec![(0, 6000), (1, 6000)]);
+        assert_eq!(
+            input.top_k_with_ties(2),
+            vec![(0, 6000), (1, 6000), (3, 6000), (4, 6000)]
+        );
+        assert_eq!(input.top_k(10).len(), 5);
+        assert!(input.top_k(0).is_empty());
+        assert!(input.top_k_with_ties(0).is_empty());
     }
 
     #[test]
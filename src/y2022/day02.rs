@@ -1,123 +1,501 @@
-use std::convert::TryFrom;
+use lazy_static::lazy_static;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::str::FromStr;
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-pub enum Hand {
-    Rock = 0,
-    Paper = 1,
-    Scissors = 2,
+lazy_static! {
+    static ref ROCK_PAPER_SCISSORS: Game = Game::rock_paper_scissors();
+    static ref PUZZLE_LETTERS: LetterMap = LetterMap::sequential(3, 'A', 'X');
 }
 
-#[derive(Copy, Clone)]
+// A hand is its position in the game's table.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Hand(pub usize);
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum RoundResult {
-    Loss = 0,
-    Draw = 3,
-    Win = 6,
+    Loss,
+    Draw,
+    Win,
 }
 
-impl Hand {
-    /**
-       Rock = 1 point
-       Paper = 2 points
-       Scissors = 3 points
-    */
-    fn value(self) -> u32 {
-        self as u32 + 1
+#[derive(Debug, PartialEq)]
+pub enum GameError {
+    EvenNumberOfHands(usize),
+    DuplicateHand(String),
+    // the scoring has to give every hand a value
+    WrongNumberOfHandValues { expected: usize, actual: usize },
+}
+
+#[derive(Clone, Debug)]
+pub struct Scoring {
+    pub loss: u32,
+    pub draw: u32,
+    pub win: u32,
+    pub hand_values: Vec<u32>,
+}
+
+impl Scoring {
+    // the puzzle's rules: 0, 3 or 6 for the outcome plus 1, 2, 3... for the hand
+    pub fn standard(size: usize) -> Self {
+        Scoring {
+            loss: 0,
+            draw: 3,
+            win: 6,
+            hand_values: (1..=size as u32).collect(),
+        }
     }
 
-    fn against(self, other: Hand) -> RoundResult {
-        match self as i8 - other as i8 {
-            0 => RoundResult::Draw,
-            1 | -2 => RoundResult::Win,
-            -1 | 2 => RoundResult::Loss,
-            _ => panic!("unreachable"),
+    fn outcome(&self, result: RoundResult) -> u32 {
+        match result {
+            RoundResult::Loss => self.loss,
+            RoundResult::Draw => self.draw,
+            RoundResult::Win => self.win,
         }
     }
 }
 
-impl From<&str> for Hand {
-    fn from(input: &str) -> Self {
-        match input {
-            "A" | "X" => Self::Rock,
-            "B" | "Y" => Self::Paper,
-            "C" | "Z" => Self::Scissors,
+// A cyclic dominance game: the hands are listed in a table, and every hand
+// beats the half of the other hands that come right before it (wrapping
+// around), so it loses to the half that comes right after it.
+#[derive(Clone, Debug)]
+pub struct Game {
+    hands: Vec<String>,
+    scoring: Scoring,
+}
+
+impl Game {
+    pub fn new(hands: Vec<String>) -> Result<Self, GameError> {
+        if hands.len().is_multiple_of(2) {
+            return Err(GameError::EvenNumberOfHands(hands.len()));
+        }
+        for (i, hand) in hands.iter().enumerate() {
+            if hands[..i].contains(hand) {
+                return Err(GameError::DuplicateHand(hand.clone()));
+            }
+        }
+
+        let scoring = Scoring::standard(hands.len());
+        Ok(Game { hands, scoring })
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        "rock paper scissors".parse().unwrap()
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        "rock spock paper lizard scissors".parse().unwrap()
+    }
+
+    pub fn with_scoring(mut self, scoring: Scoring) -> Result<Self, GameError> {
+        if scoring.hand_values.len() != self.size() {
+            return Err(GameError::WrongNumberOfHandValues {
+                expected: self.size(),
+                actual: scoring.hand_values.len(),
+            });
+        }
+
+        self.scoring = scoring;
+        Ok(self)
+    }
+
+    pub fn size(&self) -> usize {
+        self.hands.len()
+    }
+
+    pub fn hands(&self) -> impl DoubleEndedIterator<Item = Hand> {
+        (0..self.size()).map(Hand)
+    }
+
+    pub fn hand(&self, name: &str) -> Option<Hand> {
+        self.hands.iter().position(|hand| hand == name).map(Hand)
+    }
+
+    pub fn name(&self, hand: Hand) -> &str {
+        &self.hands[hand.0]
+    }
+
+    pub fn play(&self, me: Hand, opponent: Hand) -> RoundResult {
+        let steps = (me.0 + self.size() - opponent.0) % self.size();
+
+        if steps == 0 {
+            RoundResult::Draw
+        } else if steps <= self.size() / 2 {
+            RoundResult::Win
+        } else {
+            RoundResult::Loss
+        }
+    }
+
+    pub fn score(&self, me: Hand, opponent: Hand) -> u32 {
+        self.scoring.outcome(self.play(me, opponent)) + self.scoring.hand_values[me.0]
+    }
+
+    // The hand that gets `result` against `opponent`. Bigger games have
+    // several of those, in which case the most valuable one is picked.
+    pub fn hand_for(&self, opponent: Hand, result: RoundResult) -> Hand {
+        self.hands()
+            .filter(|&me| self.play(me, opponent) == result)
+            .max_by_key(|&me| (self.scoring.hand_values[me.0], std::cmp::Reverse(me)))
+            .unwrap()
+    }
+
+    // the hand scoring the most on average against the hands seen so far,
+    // the first one in the table on ties
+    pub fn best_response(&self, observed: &[Hand]) -> Hand {
+        let mut counts = vec![0; self.size()];
+        for hand in observed {
+            counts[hand.0] += 1;
+        }
+
+        self.hands()
+            .rev()
+            .max_by_key(|&me| {
+                self.hands()
+                    .map(|opponent| counts[opponent.0] * self.score(me, opponent))
+                    .sum::<u32>()
+            })
+            .unwrap()
+    }
+}
+
+// reads the table as hand names separated by whitespace, in cyclic order
+impl FromStr for Game {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Game::new(s.split_whitespace().map(str::to_string).collect())
+    }
+}
+
+// What the letters in a strategy guide stand for.
+#[derive(Clone, Debug, Default)]
+pub struct LetterMap {
+    pub opponent: HashMap<char, Hand>,
+    pub own: HashMap<char, Hand>,
+    pub results: HashMap<char, RoundResult>,
+}
+
+impl LetterMap {
+    // consecutive letters for the hands, starting at `opponent_start` and
+    // `own_start`; the three letters from `own_start` also mean lose, draw, win
+    pub fn sequential(size: usize, opponent_start: char, own_start: char) -> Self {
+        let letters = |start: char| (start as u8..).map(char::from);
+
+        LetterMap {
+            opponent: letters(opponent_start).zip((0..size).map(Hand)).collect(),
+            own: letters(own_start).zip((0..size).map(Hand)).collect(),
+            results: letters(own_start)
+                .zip(vec![RoundResult::Loss, RoundResult::Draw, RoundResult::Win])
+                .collect(),
+        }
+    }
+
+    fn lookup<T: Copy>(letters: &HashMap<char, T>, letter: &str) -> T {
+        let mut chars = letter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => *letters.get(&c).expect("input is corrupt"),
             _ => panic!("input is corrupt"),
         }
     }
+
+    // each line holds the opponent's letter and one about our side
+    fn columns(input: &str) -> impl Iterator<Item = (&str, &str)> {
+        input
+            .lines()
+            .map(|line| line.split_once(' ').expect("input is corrupt"))
+    }
+
+    // guide lines read as opponent hand, own hand
+    pub fn parse_hands(&self, input: &str) -> Vec<(Hand, Hand)> {
+        Self::columns(input)
+            .map(|(opponent, own)| {
+                (
+                    Self::lookup(&self.opponent, opponent),
+                    Self::lookup(&self.own, own),
+                )
+            })
+            .collect()
+    }
+
+    // guide lines read as opponent hand, desired result
+    pub fn parse_results(&self, input: &str) -> Vec<(Hand, RoundResult)> {
+        Self::columns(input)
+            .map(|(opponent, result)| {
+                (
+                    Self::lookup(&self.opponent, opponent),
+                    Self::lookup(&self.results, result),
+                )
+            })
+            .collect()
+    }
+}
+
+pub fn score_hands(game: &Game, rounds: &[(Hand, Hand)]) -> u32 {
+    rounds
+        .iter()
+        .map(|&(opponent, me)| game.score(me, opponent))
+        .sum()
 }
 
-impl TryFrom<i8> for Hand {
-    type Error = ();
+pub fn score_results(game: &Game, rounds: &[(Hand, RoundResult)]) -> u32 {
+    rounds
+        .iter()
+        .map(|&(opponent, result)| game.score(game.hand_for(opponent, result), opponent))
+        .sum()
+}
 
-    fn try_from(v: i8) -> Result<Self, Self::Error> {
-        match v {
-            x if x == Hand::Rock as i8 => Ok(Hand::Rock),
-            x if x == Hand::Paper as i8 => Ok(Hand::Paper),
-            x if x == Hand::Scissors as i8 => Ok(Hand::Scissors),
-            _ => Err(()),
+pub trait Strategy {
+    fn name(&self) -> String;
+
+    // `history` lists the earlier rounds of the match as (own, opponent) hands
+    fn choose(&mut self, game: &Game, history: &[(Hand, Hand)]) -> Hand;
+
+    // forgets everything learned, ready for a new match
+    fn reset(&mut self) {}
+}
+
+// Plays the given hands in order, starting over when they run out.
+pub struct FixedSequence {
+    name: String,
+    hands: Vec<Hand>,
+}
+
+impl FixedSequence {
+    pub fn new(name: &str, hands: Vec<Hand>) -> Self {
+        assert!(
+            !hands.is_empty(),
+            "a fixed sequence needs at least one hand"
+        );
+
+        FixedSequence {
+            name: name.to_string(),
+            hands,
         }
     }
+
+    // our own column of a strategy guide
+    pub fn from_guide(name: &str, letters: &LetterMap, input: &str) -> Self {
+        let hands = letters
+            .parse_hands(input)
+            .into_iter()
+            .map(|(_, own)| own)
+            .collect();
+        Self::new(name, hands)
+    }
 }
 
-impl From<&str> for RoundResult {
-    fn from(input: &str) -> Self {
-        match input {
-            "X" => Self::Loss,
-            "Y" => Self::Draw,
-            "Z" => Self::Win,
-            _ => panic!("input is corrupt"),
+impl Strategy for FixedSequence {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn choose(&mut self, _: &Game, history: &[(Hand, Hand)]) -> Hand {
+        self.hands[history.len() % self.hands.len()]
+    }
+}
+
+pub struct UniformRandom {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl UniformRandom {
+    pub fn seeded(seed: u64) -> Self {
+        UniformRandom {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for UniformRandom {
+    fn name(&self) -> String {
+        format!("random({})", self.seed)
+    }
+
+    fn choose(&mut self, game: &Game, _: &[(Hand, Hand)]) -> Hand {
+        Hand(self.rng.gen_range(0..game.size()))
+    }
+
+    fn reset(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+}
+
+// Counts the opponent's hands and plays the best response to them.
+pub struct FrequencyCounter;
+
+impl Strategy for FrequencyCounter {
+    fn name(&self) -> String {
+        "frequency counter".to_string()
+    }
+
+    fn choose(&mut self, game: &Game, history: &[(Hand, Hand)]) -> Hand {
+        let observed: Vec<Hand> = history.iter().map(|&(_, opponent)| opponent).collect();
+        game.best_response(&observed)
+    }
+}
+
+// Keeps a winning hand, otherwise switches to the hand that would have
+// beaten the opponent's last one.
+pub struct WinStayLoseShift {
+    pub opening: Hand,
+}
+
+impl Strategy for WinStayLoseShift {
+    fn name(&self) -> String {
+        "win-stay lose-shift".to_string()
+    }
+
+    fn choose(&mut self, game: &Game, history: &[(Hand, Hand)]) -> Hand {
+        match history.last() {
+            None => self.opening,
+            Some(&(own, opponent)) if game.play(own, opponent) == RoundResult::Win => own,
+            Some(&(_, opponent)) => game.hand_for(opponent, RoundResult::Win),
         }
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MatchResult {
+    pub score: u32,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+// Plays `rounds` rounds, returning how it went for each side.
+pub fn play_match(
+    game: &Game,
+    first: &mut dyn Strategy,
+    second: &mut dyn Strategy,
+    rounds: usize,
+) -> (MatchResult, MatchResult) {
+    first.reset();
+    second.reset();
+
+    let mut first_history = Vec::with_capacity(rounds);
+    let mut second_history = Vec::with_capacity(rounds);
+    let mut results = (MatchResult::default(), MatchResult::default());
+
+    for _ in 0..rounds {
+        let a = first.choose(game, &first_history);
+        let b = second.choose(game, &second_history);
+
+        for (result, me, opponent) in [(&mut results.0, a, b), (&mut results.1, b, a)] {
+            result.score += game.score(me, opponent);
+            match game.play(me, opponent) {
+                RoundResult::Win => result.wins += 1,
+                RoundResult::Draw => result.draws += 1,
+                RoundResult::Loss => result.losses += 1,
+            }
+        }
+
+        first_history.push((a, b));
+        second_history.push((b, a));
+    }
+
+    results
+}
+
+pub struct Standings {
+    pub names: Vec<String>,
+    // results[i][j] is how strategy i did against strategy j
+    pub results: Vec<Vec<MatchResult>>,
+}
+
+impl Standings {
+    pub fn total_score(&self, strategy: usize) -> u32 {
+        self.results[strategy]
+            .iter()
+            .map(|result| result.score)
+            .sum()
+    }
+
+    // share of all rounds played that were won
+    pub fn win_rate(&self, strategy: usize) -> f64 {
+        let (wins, played) =
+            self.results[strategy]
+                .iter()
+                .fold((0, 0), |(wins, played), result| {
+                    (
+                        wins + result.wins,
+                        played + result.wins + result.draws + result.losses,
+                    )
+                });
+
+        if played == 0 {
+            0.0
+        } else {
+            wins as f64 / played as f64
+        }
+    }
+
+    // one row per strategy with its score against every opponent
+    pub fn table(&self) -> String {
+        let width = self.names.iter().map(String::len).max().unwrap_or(0);
+
+        let mut table = format!("{:width$}", "", width = width);
+        for i in 0..self.names.len() {
+            table += &format!(" {:>8}", i + 1);
+        }
+        table += "    total win rate\n";
+
+        for (i, name) in self.names.iter().enumerate() {
+            table += &format!("{:width$}", name, width = width);
+            for result in &self.results[i] {
+                table += &format!(" {:>8}", result.score);
+            }
+            table += &format!(
+                " {:>8} {:>7.1}%\n",
+                self.total_score(i),
+                100.0 * self.win_rate(i)
+            );
+        }
+
+        table
+    }
+}
+
+// Every strategy plays one match against every other one.
+pub fn round_robin(game: &Game, strategies: &mut [Box<dyn Strategy>], rounds: usize) -> Standings {
+    let count = strategies.len();
+    let mut results = vec![vec![MatchResult::default(); count]; count];
+
+    let pairings = (0..count).flat_map(|i| (i + 1..count).map(move |j| (i, j)));
+    for (i, j) in pairings {
+        let (left, right) = strategies.split_at_mut(j);
+        let (a, b) = play_match(game, left[i].as_mut(), right[0].as_mut(), rounds);
+        results[i][j] = a;
+        results[j][i] = b;
+    }
+
+    Standings {
+        names: strategies.iter().map(|strategy| strategy.name()).collect(),
+        results,
+    }
+}
+
 #[aoc_generator(day02, part1)]
-pub fn generate_input_part1(input: &str) -> Vec<Vec<Hand>> {
-    input
-        .lines()
-        // line consists of opponent hand, own hand
-        .map(|line| line.split(" ").map(|c| Hand::from(c)).collect())
-        .collect()
+pub fn generate_input_part1(input: &str) -> Vec<(Hand, Hand)> {
+    PUZZLE_LETTERS.parse_hands(input)
 }
 
 #[aoc_generator(day02, part2)]
 pub fn generate_input_part2(input: &str) -> Vec<(Hand, RoundResult)> {
-    input
-        .lines()
-        .map(|line| {
-            // line consists of opponent hand, and result
-            let v: Vec<&str> = line.split(" ").collect();
-            (Hand::from(v[0]), RoundResult::from(v[1]))
-        })
-        .collect()
+    PUZZLE_LETTERS.parse_results(input)
 }
 
 #[aoc(day02, part1)]
-pub fn solve_part1(input: &Vec<Vec<Hand>>) -> u32 {
-    input
-        .iter()
-        .map(|v| {
-            let opponent = v[0];
-            let me = v[1];
-            me.against(opponent) as u32 + me.value()
-        })
-        .sum()
+pub fn solve_part1(input: &[(Hand, Hand)]) -> u32 {
+    score_hands(&ROCK_PAPER_SCISSORS, input)
 }
 
 #[aoc(day02, part2)]
-pub fn solve_part2(input: &Vec<(Hand, RoundResult)>) -> u32 {
-    input
-        .iter()
-        .map(|(opponent, result)| {
-            let me = match result {
-                RoundResult::Draw => *opponent,
-                // use previous possible hand, ie opponent is Paper, choose Rock
-                RoundResult::Loss => Hand::try_from(((*opponent as i8) - 1 + 3) % 3).unwrap(),
-                // use next possible hand, ie opponent is Rock, choose Paper
-                RoundResult::Win => Hand::try_from(((*opponent as i8) + 1) % 3).unwrap(),
-            };
-
-            *result as u32 + me.value()
-        })
-        .sum()
+pub fn solve_part2(input: &[(Hand, RoundResult)]) -> u32 {
+    score_results(&ROCK_PAPER_SCISSORS, input)
 }
 
 #[cfg(test)]
@@ -125,7 +503,7 @@ mod tests {
     use super::*;
     use std::fs;
 
-    fn get_input_part_1() -> Vec<Vec<Hand>> {
+    fn get_input_part_1() -> Vec<(Hand, Hand)> {
         let text = "A Y
 B X
 C Z";
@@ -153,6 +531,146 @@ C Z";
         assert_eq!(12, solve_part2(&input));
     }
 
+    #[test]
+    fn example_lizard_spock() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let hand = |name| game.hand(name).unwrap();
+
+        for (winner, loser) in vec![
+            ("scissors", "paper"),
+            ("paper", "rock"),
+            ("rock", "lizard"),
+            ("lizard", "spock"),
+            ("spock", "scissors"),
+            ("scissors", "lizard"),
+            ("lizard", "paper"),
+            ("paper", "spock"),
+            ("spock", "rock"),
+            ("rock", "scissors"),
+        ] {
+            assert_eq!(game.play(hand(winner), hand(loser)), RoundResult::Win);
+            assert_eq!(game.play(hand(loser), hand(winner)), RoundResult::Loss);
+        }
+        assert_eq!(game.play(hand("spock"), hand("spock")), RoundResult::Draw);
+
+        // of the two hands beating rock, paper is worth more than spock
+        assert_eq!(game.hand_for(hand("rock"), RoundResult::Win), hand("paper"));
+        assert_eq!(
+            game.hand_for(hand("paper"), RoundResult::Loss),
+            hand("spock")
+        );
+
+        let letters = LetterMap::sequential(5, 'A', 'V');
+        let guide = "A W\nE V\nC Z";
+        assert_eq!(
+            score_hands(&game, &letters.parse_hands(guide)),
+            (2 + 6) + (1 + 6) + (5 + 6)
+        );
+
+        let letters = LetterMap::sequential(5, 'A', 'X');
+        assert_eq!(
+            score_results(&game, &letters.parse_results("A Z\nB X\nD Y")),
+            (3 + 6) + 5 + (4 + 3)
+        );
+    }
+
+    #[test]
+    fn example_custom_rules() {
+        assert_eq!(
+            "rock paper".parse::<Game>().err(),
+            Some(GameError::EvenNumberOfHands(2))
+        );
+        assert_eq!(
+            "a b a".parse::<Game>().err(),
+            Some(GameError::DuplicateHand("a".to_string()))
+        );
+
+        let scoring = Scoring {
+            loss: 0,
+            draw: 1,
+            win: 2,
+            hand_values: vec![0; 7],
+        };
+        let game: Game = "a b c d e f g".parse().unwrap();
+        assert_eq!(
+            game.clone().with_scoring(Scoring::standard(3)).err(),
+            Some(GameError::WrongNumberOfHandValues {
+                expected: 7,
+                actual: 3
+            })
+        );
+
+        let game = game.with_scoring(scoring).unwrap();
+        let wins = game
+            .hands()
+            .filter(|&me| game.score(me, Hand(0)) == 2)
+            .count();
+        assert_eq!(wins, 3);
+        assert_eq!(game.score(Hand(6), Hand(0)), 0);
+        assert_eq!(game.hand_for(Hand(0), RoundResult::Loss), Hand(4));
+        assert_eq!(game.hand_for(Hand(0), RoundResult::Draw), Hand(0));
+
+        let rounds = LetterMap::sequential(7, 'A', 'N').parse_hands("A N\nG O\nC P");
+        assert_eq!(score_hands(&game, &rounds), 1 + 2 + 1);
+    }
+
+    #[test]
+    fn example_strategies() {
+        let game = Game::rock_paper_scissors();
+        let (rock, paper, scissors) = (Hand(0), Hand(1), Hand(2));
+
+        assert_eq!(game.best_response(&[]), rock);
+        assert_eq!(game.best_response(&[rock, rock, scissors]), paper);
+        // rock wins the most rounds, but scissors is worth more points
+        assert_eq!(game.best_response(&[scissors, paper, scissors]), scissors);
+
+        let mut guide = FixedSequence::from_guide("guide", &PUZZLE_LETTERS, "A Y\nB X\nC Z");
+        let mut counter = FrequencyCounter;
+        let (a, b) = play_match(&game, &mut guide, &mut counter, 6);
+        assert_eq!(a.wins + a.draws + a.losses, 6);
+        assert_eq!((a.wins, b.wins), (b.losses, a.losses));
+
+        let mut shifter = WinStayLoseShift { opening: rock };
+        let mut always_paper = FixedSequence::new("paper", vec![paper]);
+        let (a, b) = play_match(&game, &mut shifter, &mut always_paper, 4);
+        assert_eq!((a.wins, a.losses), (3, 1));
+        assert_eq!(a.score, 1 + 3 * (3 + 6));
+        assert_eq!(b.score, (2 + 6) + 3 * 2);
+
+        let mut random = UniformRandom::seeded(7);
+        let first: Vec<Hand> = (0..20).map(|_| random.choose(&game, &[])).collect();
+        random.reset();
+        let second: Vec<Hand> = (0..20).map(|_| random.choose(&game, &[])).collect();
+        assert_eq!(first, second);
+        assert!(first.iter().all(|hand| hand.0 < 3));
+    }
+
+    #[test]
+    fn example_round_robin() {
+        let game = Game::rock_paper_scissors();
+        let mut strategies: Vec<Box<dyn Strategy>> = vec![
+            Box::new(FixedSequence::new("rock", vec![Hand(0)])),
+            Box::new(FrequencyCounter),
+            Box::new(WinStayLoseShift { opening: Hand(2) }),
+            Box::new(UniformRandom::seeded(42)),
+        ];
+
+        let standings = round_robin(&game, &mut strategies, 100);
+
+        for i in 0..4 {
+            assert_eq!(standings.results[i][i], MatchResult::default());
+            for j in 0..4 {
+                assert_eq!(standings.results[i][j].wins, standings.results[j][i].losses);
+            }
+        }
+
+        // rock never changes, so the counter beats it from the second round on
+        assert_eq!(standings.results[1][0].wins, 99);
+        assert!(standings.win_rate(0) < standings.win_rate(1));
+        assert_eq!(standings.table().lines().count(), 5);
+        assert!(standings.table().starts_with("                  "));
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2022/day2.txt").unwrap();
@@ -1,72 +1,180 @@
-use itertools::Itertools;
-use std::collections::HashSet;
+use lazy_static::lazy_static;
 
-#[aoc_generator(day03)]
-pub fn generate_input(input: &str) -> Vec<Vec<char>> {
-    input
-        .lines()
-        .map(|rucksack| rucksack.chars().collect())
-        .collect()
+lazy_static! {
+    static ref PUZZLE_ALPHABET: Alphabet =
+        Alphabet::new("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap();
+}
+
+// Lines count from one in every error.
+#[derive(Debug, PartialEq)]
+pub enum RucksackError {
+    AlphabetTooLarge(usize),
+    UnknownItem { line: usize, item: char },
+    OddNumberOfItems { line: usize },
+    NoCommonItem { lines: Vec<usize> },
+    SeveralCommonItems { lines: Vec<usize>, items: Vec<char> },
+    IncompleteGroup { lines: Vec<usize> },
+    EmptyGroup,
+}
+
+// The item types that can show up, in priority order. Each one is a bit in
+// a u64, so there can be at most 64 of them.
+pub struct Alphabet {
+    items: Vec<char>,
 }
 
-fn value_of_item(item: &char) -> u32 {
-    match item {
-        'a'..='z' => *item as u32 - b'a' as u32 + 1,
-        'A'..='Z' => *item as u32 - b'A' as u32 + 27,
-        _ => panic!("corrupt intput"),
+impl Alphabet {
+    pub fn new(items: &str) -> Result<Self, RucksackError> {
+        let items: Vec<char> = items.chars().collect();
+        if items.len() > 64 {
+            return Err(RucksackError::AlphabetTooLarge(items.len()));
+        }
+
+        Ok(Alphabet { items })
+    }
+
+    fn bit(&self, item: char) -> Option<u64> {
+        self.items
+            .iter()
+            .position(|&c| c == item)
+            .map(|index| 1 << index)
+    }
+
+    fn items_in(&self, mask: u64) -> Vec<char> {
+        (0..self.items.len())
+            .filter(|index| mask & (1 << index) != 0)
+            .map(|index| self.items[index])
+            .collect()
+    }
+
+    pub fn priority(&self, item: char) -> Option<u32> {
+        self.bit(item).map(|bit| bit.trailing_zeros() + 1)
     }
 }
 
-#[aoc(day03, part1)]
-pub fn solve_part1(input: &Vec<Vec<char>>) -> u32 {
-    input
-        .iter()
-        .map(|rucksack| {
-            let (compartment1, compartment2) = rucksack.split_at(rucksack.len() / 2);
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rucksack {
+    pub line: usize,
+    pub first: u64,
+    pub second: u64,
+}
 
-            let unique_in_compartment1: HashSet<&char> = compartment1.iter().collect();
-            let unique_in_compartment2: HashSet<&char> = compartment2.iter().collect();
+impl Rucksack {
+    pub fn parse(alphabet: &Alphabet, line: usize, contents: &str) -> Result<Self, RucksackError> {
+        let items = contents
+            .chars()
+            .map(|item| {
+                alphabet
+                    .bit(item)
+                    .ok_or(RucksackError::UnknownItem { line, item })
+            })
+            .collect::<Result<Vec<u64>, _>>()?;
 
-            unique_in_compartment1
-                .intersection(&unique_in_compartment2)
-                .map(|item| value_of_item(*item))
-                .sum::<u32>()
+        if items.len() % 2 != 0 {
+            return Err(RucksackError::OddNumberOfItems { line });
+        }
+        let (first, second) = items.split_at(items.len() / 2);
+
+        Ok(Rucksack {
+            line,
+            first: first.iter().fold(0, |mask, bit| mask | bit),
+            second: second.iter().fold(0, |mask, bit| mask | bit),
         })
-        .sum()
+    }
+
+    pub fn items(&self) -> u64 {
+        self.first | self.second
+    }
 }
 
-#[aoc(day03, part2)]
-pub fn solve_part2(input: &Vec<Vec<char>>) -> u32 {
-    let chunk_size = 3;
+// the priority of the one item all the masks share
+fn common_priority(
+    alphabet: &Alphabet,
+    masks: &[u64],
+    lines: Vec<usize>,
+) -> Result<u32, RucksackError> {
+    let common = masks.iter().fold(u64::MAX, |common, mask| common & mask);
+
+    match common.count_ones() {
+        1 => Ok(common.trailing_zeros() + 1),
+        0 => Err(RucksackError::NoCommonItem { lines }),
+        _ => Err(RucksackError::SeveralCommonItems {
+            lines,
+            items: alphabet.items_in(common),
+        }),
+    }
+}
+
+pub fn parse_rucksacks(alphabet: &Alphabet, input: &str) -> Result<Vec<Rucksack>, RucksackError> {
     input
+        .lines()
+        .enumerate()
+        .map(|(i, contents)| Rucksack::parse(alphabet, i + 1, contents))
+        .collect()
+}
+
+// the item in both compartments of each rucksack
+pub fn misplaced_priorities(
+    alphabet: &Alphabet,
+    rucksacks: &[Rucksack],
+) -> Result<u32, RucksackError> {
+    rucksacks
         .iter()
-        .chunks(chunk_size) //
-        .into_iter()
-        .map(|group| group.map(|r| r.iter().collect::<HashSet<&char>>()))
+        .map(|rucksack| {
+            common_priority(
+                alphabet,
+                &[rucksack.first, rucksack.second],
+                vec![rucksack.line],
+            )
+        })
+        .sum()
+}
+
+// the item carried by every elf of each group of `group_size` rucksacks
+pub fn badge_priorities(
+    alphabet: &Alphabet,
+    rucksacks: &[Rucksack],
+    group_size: usize,
+) -> Result<u32, RucksackError> {
+    if group_size == 0 {
+        return Err(RucksackError::EmptyGroup);
+    }
+
+    rucksacks
+        .chunks(group_size)
         .map(|group| {
-            let group: Vec<HashSet<&char>> = group.collect();
-            let rucksack1 = &group[0];
-            let rucksack2 = &group[1];
-            let rucksack3 = &group[2];
-
-            let intersection: HashSet<&char> = rucksack1
-                .intersection(rucksack2)
-                .map(|c| *c)
-                .collect::<HashSet<&char>>();
-            intersection
-                .intersection(rucksack3)
-                .map(|item| value_of_item(item))
-                .sum::<u32>()
+            let lines = group.iter().map(|rucksack| rucksack.line).collect();
+            if group.len() < group_size {
+                return Err(RucksackError::IncompleteGroup { lines });
+            }
+
+            let masks: Vec<u64> = group.iter().map(Rucksack::items).collect();
+            common_priority(alphabet, &masks, lines)
         })
         .sum()
 }
 
+#[aoc_generator(day03)]
+pub fn generate_input(input: &str) -> Vec<Rucksack> {
+    parse_rucksacks(&PUZZLE_ALPHABET, input).unwrap()
+}
+
+#[aoc(day03, part1)]
+pub fn solve_part1(input: &[Rucksack]) -> u32 {
+    misplaced_priorities(&PUZZLE_ALPHABET, input).unwrap()
+}
+
+#[aoc(day03, part2)]
+pub fn solve_part2(input: &[Rucksack]) -> u32 {
+    badge_priorities(&PUZZLE_ALPHABET, input, 3).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
 
-    fn get_input() -> Vec<Vec<char>> {
+    fn get_input() -> Vec<Rucksack> {
         let text = "vJrwpWtwJgWrhcsFMMfFFhFp
 jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
 PmmdzqPrVvPwwTWBwg
@@ -91,6 +199,96 @@ CrZsJsPPZsGzwwsLwLmpwMDw
         assert_eq!(solve_part2(&input), 70);
     }
 
+    #[test]
+    fn example_group_sizes() {
+        let input = get_input();
+        let alphabet = &PUZZLE_ALPHABET;
+
+        assert_eq!(alphabet.priority('p'), Some(16));
+        assert_eq!(alphabet.priority('L'), Some(38));
+        assert_eq!(alphabet.priority('1'), None);
+
+        // the first two rucksacks of each group share more than the badge
+        assert_eq!(
+            badge_priorities(alphabet, &input, 2),
+            Err(RucksackError::SeveralCommonItems {
+                lines: vec![1, 2],
+                items: vec!['f', 'r', 's', 'F', 'M'],
+            })
+        );
+        assert_eq!(
+            badge_priorities(alphabet, &input, 6),
+            Err(RucksackError::NoCommonItem {
+                lines: vec![1, 2, 3, 4, 5, 6]
+            })
+        );
+        assert_eq!(
+            badge_priorities(alphabet, &input, 4),
+            Err(RucksackError::NoCommonItem {
+                lines: vec![1, 2, 3, 4]
+            })
+        );
+        assert_eq!(
+            badge_priorities(alphabet, &input[..5], 3),
+            Err(RucksackError::IncompleteGroup { lines: vec![4, 5] })
+        );
+        assert_eq!(
+            badge_priorities(alphabet, &input, 0),
+            Err(RucksackError::EmptyGroup)
+        );
+        assert_eq!(
+            badge_priorities(alphabet, &input[..1], 1),
+            Err(RucksackError::SeveralCommonItems {
+                lines: vec![1],
+                items: alphabet.items_in(input[0].items()),
+            })
+        );
+    }
+
+    #[test]
+    fn example_errors() {
+        let alphabet = Alphabet::new("abc").unwrap();
+
+        assert_eq!(
+            parse_rucksacks(&alphabet, "abca\nabd"),
+            Err(RucksackError::UnknownItem { line: 2, item: 'd' })
+        );
+        assert_eq!(
+            parse_rucksacks(&alphabet, "aa\nabc"),
+            Err(RucksackError::OddNumberOfItems { line: 2 })
+        );
+
+        let rucksacks = parse_rucksacks(&alphabet, "abca\nabab\nccab").unwrap();
+        assert_eq!(
+            misplaced_priorities(&alphabet, &rucksacks),
+            Err(RucksackError::SeveralCommonItems {
+                lines: vec![2],
+                items: vec!['a', 'b']
+            })
+        );
+        assert_eq!(
+            misplaced_priorities(&alphabet, &rucksacks[2..]),
+            Err(RucksackError::NoCommonItem { lines: vec![3] })
+        );
+        assert_eq!(misplaced_priorities(&alphabet, &rucksacks[..1]), Ok(1));
+        assert_eq!(
+            badge_priorities(&alphabet, &rucksacks, 3),
+            Err(RucksackError::SeveralCommonItems {
+                lines: vec![1, 2, 3],
+                items: vec!['a', 'b']
+            })
+        );
+
+        let rucksacks = parse_rucksacks(&alphabet, "ab\nbc\nbb").unwrap();
+        assert_eq!(badge_priorities(&alphabet, &rucksacks, 3), Ok(2));
+
+        let letters: String = ('!'..='~').collect();
+        assert_eq!(
+            Alphabet::new(&letters).err(),
+            Some(RucksackError::AlphabetTooLarge(94))
+        );
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2022/day3.txt").unwrap();
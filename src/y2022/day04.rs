@@ -18,7 +18,7 @@ pub fn generate_input(input: &str) -> Vec<(RangeInclusive<u32>, RangeInclusive<u
 }
 
 #[aoc(day04, part1)]
-pub fn solve_part1(input: &Vec<(RangeInclusive<u32>, RangeInclusive<u32>)>) -> usize {
+pub fn solve_part1(input: &[(RangeInclusive<u32>, RangeInclusive<u32>)]) -> usize {
     input
         .iter()
         .filter(|(elf1, elf2)| {
@@ -29,7 +29,7 @@ pub fn solve_part1(input: &Vec<(RangeInclusive<u32>, RangeInclusive<u32>)>) -> u
 }
 
 #[aoc(day04, part2)]
-pub fn solve_part2(input: &Vec<(RangeInclusive<u32>, RangeInclusive<u32>)>) -> usize {
+pub fn solve_part2(input: &[(RangeInclusive<u32>, RangeInclusive<u32>)]) -> usize {
     input
         .iter()
         .filter(|(elf1, elf2)| !(elf1.end() < elf2.start() || elf1.start() > elf2.end()))
@@ -1,125 +1,299 @@
 use itertools::Itertools;
+use std::ops::Range;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Instruction {
     amount: usize,
     from: usize,
     to: usize,
 }
 
-impl From<&str> for Instruction {
-    fn from(s: &str) -> Self {
-        let instruction_numbers: Vec<usize> = s
-            .split(' ')
-            .skip(1)
-            .step_by(2)
-            .map(|n| n.parse().unwrap())
-            .collect();
-
-        Instruction {
-            amount: instruction_numbers[0],
-            from: instruction_numbers[1] - 1,
-            to: instruction_numbers[2] - 1,
+impl Instruction {
+    // reads "move <amount> from <stack> to <stack>", stacks numbered from one
+    fn parse(line: &str) -> Option<(usize, usize, usize)> {
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            ["move", amount, "from", from, "to", to] => {
+                Some((amount.parse().ok()?, from.parse().ok()?, to.parse().ok()?))
+            }
+            _ => None,
         }
     }
 }
 
-#[aoc_generator(day05)]
-pub fn generate_input(input: &str) -> (Vec<Vec<char>>, Vec<Instruction>) {
-    let (drawing, procedure) = input.split_once("\n\n").unwrap();
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    MissingProcedure,
+    // the last line of the drawing has to number the stacks 1, 2, 3...
+    BadFooter(String),
+    // `line` and `column` count from one, like an editor would
+    BadCrate { line: usize, column: usize },
+    // `instruction` is the zero based position in the procedure
+    BadInstruction { instruction: usize },
+    NoSuchStack { instruction: usize, stack: usize },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum YardError {
+    // `instruction` is the zero based position in the procedure
+    NotEnoughCrates {
+        instruction: usize,
+        stack: usize,
+        requested: usize,
+        available: usize,
+    },
+}
+
+pub trait Crane {
+    // how many crates the crane can pick up in one go, keeping their order
+    fn lift_capacity(&self) -> usize;
+}
 
-    let first_line = drawing.lines().take(1).exactly_one().unwrap();
-    let stack_count = (first_line.len() / 4) + 1;
-    let mut crates: Vec<Vec<char>> = Vec::with_capacity(stack_count);
+pub struct CrateMover9000;
 
-    let highest = drawing.lines().count() - 1;
-    for _ in 0..stack_count {
-        crates.push(Vec::with_capacity(highest));
+impl Crane for CrateMover9000 {
+    fn lift_capacity(&self) -> usize {
+        1
     }
+}
 
-    drawing.lines().for_each(|line| {
-        line.chars()
-            .chunks(4)
-            .into_iter()
-            .map(|chunk| {
-                let chunk: Vec<char> = chunk.collect();
+pub struct CrateMover9001;
 
-                if chunk[0] == '[' {
-                    Some(chunk[1])
-                } else {
-                    None
-                }
-            })
+impl Crane for CrateMover9001 {
+    fn lift_capacity(&self) -> usize {
+        usize::MAX
+    }
+}
+
+pub struct BatchCrane {
+    pub batch_size: usize,
+}
+
+impl Crane for BatchCrane {
+    fn lift_capacity(&self) -> usize {
+        self.batch_size.max(1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrateYard {
+    // each stack lists its crates from the bottom up
+    stacks: Vec<Vec<String>>,
+}
+
+impl CrateYard {
+    pub fn new(stacks: Vec<Vec<String>>) -> Self {
+        CrateYard { stacks }
+    }
+
+    pub fn stacks(&self) -> &[Vec<String>] {
+        &self.stacks
+    }
+
+    pub fn top_crates(&self) -> String {
+        self.stacks
+            .iter()
+            .filter_map(|stack| stack.last())
+            .map(String::as_str)
+            .collect()
+    }
+
+    // carries out the instruction at position `index` of the procedure
+    pub fn step<C: Crane>(
+        &mut self,
+        crane: &C,
+        index: usize,
+        instruction: &Instruction,
+    ) -> Result<(), YardError> {
+        let available = self.stacks[instruction.from].len();
+        if available < instruction.amount {
+            return Err(YardError::NotEnoughCrates {
+                instruction: index,
+                stack: instruction.from + 1,
+                requested: instruction.amount,
+                available,
+            });
+        }
+
+        let mut lifted = self.stacks[instruction.from].split_off(available - instruction.amount);
+
+        // every lift takes the topmost crates still left in `lifted`
+        let capacity = crane.lift_capacity();
+        while !lifted.is_empty() {
+            let load = lifted.split_off(lifted.len().saturating_sub(capacity));
+            self.stacks[instruction.to].extend(load);
+        }
+
+        Ok(())
+    }
+
+    pub fn run<C: Crane>(
+        &mut self,
+        crane: &C,
+        instructions: &[Instruction],
+    ) -> Result<(), YardError> {
+        instructions
+            .iter()
             .enumerate()
-            .for_each(|(i, c)| {
-                if let Some(crate_id) = c {
-                    if crates[i].len() == 0 {
-                        crates[i].push(crate_id)
-                    } else {
-                        crates[i].splice(0..0, vec![crate_id]);
-                    }
-                }
-            })
-    });
+            .try_for_each(|(index, instruction)| self.step(crane, index, instruction))
+    }
+
+    // draws the stacks the way the puzzle input does, numbered footer included;
+    // every column is as wide as the longest label in the yard
+    pub fn render(&self) -> String {
+        let highest = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+        let width = 2 + self
+            .stacks
+            .iter()
+            .flatten()
+            .map(String::len)
+            .max()
+            .unwrap_or(1);
 
-    let instructions: Vec<Instruction> = procedure.lines().map(Instruction::from).collect();
+        let mut drawing = String::new();
+        for level in (0..highest).rev() {
+            let line = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(label) => format!("{:<width$}", format!("[{}]", label), width = width),
+                    None => " ".repeat(width),
+                })
+                .join(" ");
+            drawing += &line;
+            drawing.push('\n');
+        }
+
+        let footer = (1..=self.stacks.len())
+            .map(|number| format!("{:^width$}", number, width = width))
+            .join(" ");
+        drawing += &footer;
+        drawing.push('\n');
 
-    (crates, instructions)
+        drawing
+    }
 }
 
-fn top_crates(crates: &Vec<Vec<char>>) -> String {
-    crates
-        .iter()
-        .map(|c| c.last())
-        .filter(|c| c.is_some())
-        .map(|c| *c.unwrap())
-        .join("")
-        .to_string()
+// byte ranges of the stack numbers in the footer, in stack order
+fn footer_columns(footer: &str) -> Result<Vec<Range<usize>>, ParseError> {
+    let mut columns = vec![];
+    let mut start = None;
+    for (i, c) in footer
+        .char_indices()
+        .chain(std::iter::once((footer.len(), ' ')))
+    {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(i),
+            (true, Some(from)) => {
+                if footer[from..i] != (columns.len() + 1).to_string() {
+                    return Err(ParseError::BadFooter(footer.to_string()));
+                }
+                columns.push(from..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if columns.is_empty() {
+        return Err(ParseError::BadFooter(footer.to_string()));
+    }
+    Ok(columns)
 }
 
-fn move_one(crates: &mut Vec<Vec<char>>, from: usize, to: usize) {
-    let crate_to_move = crates[from].pop().expect("input wasn't parsed correctly");
-    crates[to].push(crate_to_move);
+// every "[label]" on a drawing line, with the byte range it covers
+fn crate_labels(line: &str) -> Result<Vec<(Range<usize>, &str)>, usize> {
+    let mut labels = vec![];
+    let mut rest = line.char_indices();
+    while let Some((start, c)) = rest.next() {
+        match c {
+            ' ' => continue,
+            '[' => match rest.find(|&(_, c)| c == ']') {
+                Some((end, _)) if end > start + 1 => {
+                    labels.push((start..end + 1, &line[start + 1..end]));
+                }
+                _ => return Err(start),
+            },
+            _ => return Err(start),
+        }
+    }
+
+    Ok(labels)
 }
 
-#[aoc(day05, part1)]
-pub fn solve_part1((crates, instructions): &(Vec<Vec<char>>, Vec<Instruction>)) -> String {
-    let mut crates: Vec<Vec<char>> = crates.clone();
+pub fn parse_input(input: &str) -> Result<(CrateYard, Vec<Instruction>), ParseError> {
+    let (drawing, procedure) = input
+        .split_once("\n\n")
+        .ok_or(ParseError::MissingProcedure)?;
+
+    let rows: Vec<&str> = drawing.lines().collect();
+    let (footer, rows) = rows
+        .split_last()
+        .ok_or_else(|| ParseError::BadFooter(String::new()))?;
+    let columns = footer_columns(footer)?;
+
+    // going from the bottom row up means every crate simply lands on top
+    let mut stacks: Vec<Vec<String>> = vec![Vec::with_capacity(rows.len()); columns.len()];
+    for (row, line) in rows.iter().enumerate().rev() {
+        let bad_crate = |start: usize| ParseError::BadCrate {
+            line: row + 1,
+            column: start + 1,
+        };
+
+        for (span, label) in crate_labels(line).map_err(bad_crate)? {
+            let stack = columns
+                .iter()
+                .position(|column| column.start < span.end && span.start < column.end)
+                .ok_or_else(|| bad_crate(span.start))?;
+            stacks[stack].push(label.to_string());
+        }
+    }
 
-    instructions.iter().for_each(|instruction| {
-        for _ in 0..instruction.amount {
-            move_one(&mut crates, instruction.from, instruction.to);
+    let stack_index = |instruction: usize, stack: usize| {
+        if (1..=stacks.len()).contains(&stack) {
+            Ok(stack - 1)
+        } else {
+            Err(ParseError::NoSuchStack { instruction, stack })
         }
-    });
+    };
+
+    let instructions = procedure
+        .lines()
+        .enumerate()
+        .map(|(instruction, line)| {
+            let (amount, from, to) =
+                Instruction::parse(line).ok_or(ParseError::BadInstruction { instruction })?;
+
+            Ok(Instruction {
+                amount,
+                from: stack_index(instruction, from)?,
+                to: stack_index(instruction, to)?,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((CrateYard::new(stacks), instructions))
+}
 
-    top_crates(&crates)
+#[aoc_generator(day05)]
+pub fn generate_input(input: &str) -> (CrateYard, Vec<Instruction>) {
+    parse_input(input).unwrap()
 }
 
-fn move_multiple(crates: &mut Vec<Vec<char>>, from: usize, to: usize, amount: usize) {
-    let from_crate = &crates[from];
-    let remove_range = (from_crate.len() - amount)..from_crate.len();
+fn rearrange<C: Crane>((yard, instructions): &(CrateYard, Vec<Instruction>), crane: &C) -> String {
+    let mut yard = yard.clone();
+    yard.run(crane, instructions).unwrap();
 
-    let mut crates_to_move: Vec<char> = crates[from].drain(remove_range).collect();
-    crates[to].append(&mut crates_to_move);
+    yard.top_crates()
 }
 
-#[aoc(day05, part2)]
-pub fn solve_part2((crates, instructions): &(Vec<Vec<char>>, Vec<Instruction>)) -> String {
-    let mut crates: Vec<Vec<char>> = crates.clone();
-
-    instructions
-        .iter()
-        .for_each(|instruction| match instruction.amount {
-            1 => move_one(&mut crates, instruction.from, instruction.to),
-            _ => move_multiple(
-                &mut crates,
-                instruction.from,
-                instruction.to,
-                instruction.amount,
-            ),
-        });
+#[aoc(day05, part1)]
+pub fn solve_part1(input: &(CrateYard, Vec<Instruction>)) -> String {
+    rearrange(input, &CrateMover9000)
+}
 
-    top_crates(&crates)
+#[aoc(day05, part2)]
+pub fn solve_part2(input: &(CrateYard, Vec<Instruction>)) -> String {
+    rearrange(input, &CrateMover9001)
 }
 
 #[cfg(test)]
@@ -127,7 +301,11 @@ mod tests {
     use super::*;
     use std::fs;
 
-    fn get_input() -> (Vec<Vec<char>>, Vec<Instruction>) {
+    fn labels(crates: &str) -> Vec<String> {
+        crates.chars().map(String::from).collect()
+    }
+
+    fn get_input() -> (CrateYard, Vec<Instruction>) {
         let text = "    [D]    
 [N] [C]    
 [Z] [M] [P]
@@ -155,6 +333,173 @@ move 1 from 1 to 2
         assert_eq!(solve_part2(&input), "MCD");
     }
 
+    #[test]
+    fn example_render() {
+        let (mut yard, instructions) = get_input();
+
+        assert_eq!(
+            yard.render(),
+            "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n"
+        );
+
+        yard.step(&CrateMover9000, 0, &instructions[0]).unwrap();
+        assert_eq!(
+            yard.render(),
+            "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n"
+        );
+
+        yard.step(&CrateMover9000, 1, &instructions[1]).unwrap();
+        assert_eq!(
+            yard.render(),
+            "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3 \n"
+        );
+    }
+
+    #[test]
+    fn example_batch_crane() {
+        let (yard, instructions) = get_input();
+
+        assert_eq!(
+            rearrange(
+                &(yard.clone(), instructions.clone()),
+                &BatchCrane { batch_size: 1 }
+            ),
+            "CMZ"
+        );
+        assert_eq!(
+            rearrange(
+                &(yard.clone(), instructions.clone()),
+                &BatchCrane { batch_size: 3 }
+            ),
+            "MCD"
+        );
+
+        let mut yard = CrateYard::new(vec![labels("ABCDE"), vec![]]);
+        let instruction = Instruction {
+            amount: 5,
+            from: 0,
+            to: 1,
+        };
+        yard.step(&BatchCrane { batch_size: 2 }, 0, &instruction)
+            .unwrap();
+        assert_eq!(yard.stacks()[1], labels("DEBCA"));
+    }
+
+    #[test]
+    fn example_not_enough_crates() {
+        let (mut yard, instructions) = get_input();
+        let greedy = Instruction {
+            amount: 3,
+            from: 1,
+            to: 0,
+        };
+
+        assert_eq!(
+            yard.run(&CrateMover9000, &[instructions[0], greedy]),
+            Err(YardError::NotEnoughCrates {
+                instruction: 1,
+                stack: 2,
+                requested: 3,
+                available: 2,
+            })
+        );
+
+        let mut empty = CrateYard::new(vec![vec![], labels("X")]);
+        assert_eq!(
+            empty.run(
+                &CrateMover9001,
+                &[Instruction {
+                    amount: 1,
+                    from: 0,
+                    to: 1
+                }]
+            ),
+            Err(YardError::NotEnoughCrates {
+                instruction: 0,
+                stack: 1,
+                requested: 1,
+                available: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn example_ragged_drawing() {
+        let text = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n\nmove 1 from 2 to 1\n";
+        let (yard, instructions) = generate_input(text);
+
+        assert_eq!(yard, get_input().0);
+        assert_eq!(instructions.len(), 1);
+    }
+
+    #[test]
+    fn example_wide_labels() {
+        let text = "     [Ab]\n[Xyz][C]\n  1    2    3\n\nmove 2 from 2 to 3\n";
+        let (mut yard, instructions) = generate_input(text);
+
+        assert_eq!(
+            yard.stacks(),
+            &[
+                vec!["Xyz".to_string()],
+                vec!["C".to_string(), "Ab".to_string()],
+                vec![]
+            ]
+        );
+
+        yard.run(&CrateMover9001, &instructions).unwrap();
+        assert_eq!(yard.top_crates(), "XyzAb");
+        assert_eq!(
+            yard.render(),
+            "            [Ab] \n[Xyz]       [C]  \n  1     2     3  \n"
+        );
+
+        let redrawn = format!("{}\nmove 1 from 3 to 1\n", yard.render());
+        assert_eq!(generate_input(&redrawn).0, yard);
+    }
+
+    #[test]
+    fn example_parse_errors() {
+        let drawing = "[A] [B]\n 1   2\n\n";
+
+        assert_eq!(
+            parse_input(&format!("{}move 1 from 1 to 3\n", drawing)),
+            Err(ParseError::NoSuchStack {
+                instruction: 0,
+                stack: 3
+            })
+        );
+        assert_eq!(
+            parse_input(&format!(
+                "{}move 1 from 2 to 1\nmove 1 from 0 to 1\n",
+                drawing
+            )),
+            Err(ParseError::NoSuchStack {
+                instruction: 1,
+                stack: 0
+            })
+        );
+        assert_eq!(
+            parse_input(&format!("{}move one from 2 to 1\n", drawing)),
+            Err(ParseError::BadInstruction { instruction: 0 })
+        );
+        assert_eq!(
+            parse_input("[A] [B]\n 1   3\n\nmove 1 from 1 to 2\n"),
+            Err(ParseError::BadFooter(" 1   3".to_string()))
+        );
+        assert_eq!(
+            parse_input("[A] [B]     [C]\n 1   2\n\nmove 1 from 1 to 2\n"),
+            Err(ParseError::BadCrate {
+                line: 1,
+                column: 13
+            })
+        );
+        assert_eq!(
+            parse_input("[A] [B\n 1   2\n\nmove 1 from 1 to 2\n"),
+            Err(ParseError::BadCrate { line: 1, column: 5 })
+        );
+        assert_eq!(parse_input("[A]\n 1\n"), Err(ParseError::MissingProcedure));
+    }
+
     #[test]
     fn test_input_part1() {
         let text = fs::read_to_string("input/2022/day5.txt").unwrap();
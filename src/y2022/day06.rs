@@ -1,33 +1,143 @@
-use itertools::Itertools;
+use std::io::{self, BufRead, BufReader, Read};
 
-#[aoc_generator(day06)]
-pub fn generate_input(input: &str) -> Vec<char> {
-    input.chars().collect()
+// Keeps the last `window_size` bytes of a stream together with how often
+// each byte value occurs among them, so every new byte is checked in O(1).
+pub struct MarkerDetector {
+    window: Vec<u8>,
+    counts: [u32; 256],
+    // byte values occurring more than once in the window
+    duplicates: usize,
+    consumed: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(window_size: usize) -> Self {
+        assert!(
+            window_size > 0,
+            "markers need a window of at least one byte"
+        );
+
+        MarkerDetector {
+            window: vec![0; window_size],
+            counts: [0; 256],
+            duplicates: 0,
+            consumed: 0,
+        }
+    }
+
+    // feeds the next byte, returning whether the window now ends in a marker
+    pub fn push(&mut self, byte: u8) -> bool {
+        let size = self.window.len();
+        let slot = self.consumed % size;
+
+        if self.consumed >= size {
+            let old = self.window[slot] as usize;
+            self.counts[old] -= 1;
+            if self.counts[old] == 1 {
+                self.duplicates -= 1;
+            }
+        }
+
+        self.window[slot] = byte;
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.duplicates += 1;
+        }
+        self.consumed += 1;
+
+        self.consumed >= size && self.duplicates == 0
+    }
+
+    // how many bytes have been pushed so far
+    pub fn position(&self) -> usize {
+        self.consumed
+    }
+}
+
+// Positions just past every marker in `bytes`, counted like the puzzle does.
+pub struct Markers<I> {
+    bytes: I,
+    detector: MarkerDetector,
+}
+
+impl<I: Iterator<Item = u8>> Iterator for Markers<I> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let detector = &mut self.detector;
+        self.bytes
+            .by_ref()
+            .find(|&byte| detector.push(byte))
+            .map(|_| detector.position())
+    }
+}
+
+pub fn markers<I>(bytes: I, window_size: usize) -> Markers<I::IntoIter>
+where
+    I: IntoIterator<Item = u8>,
+{
+    Markers {
+        bytes: bytes.into_iter(),
+        detector: MarkerDetector::new(window_size),
+    }
+}
+
+// Streams `reader` through a buffer, calling `found` with every marker
+// position until it returns false or the input runs out.
+pub fn scan_markers<R, F>(reader: R, window_size: usize, mut found: F) -> io::Result<()>
+where
+    R: Read,
+    F: FnMut(usize) -> bool,
+{
+    let mut reader = BufReader::new(reader);
+    let mut detector = MarkerDetector::new(window_size);
+
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let length = buffer.len();
+        for &byte in buffer {
+            if detector.push(byte) && !found(detector.position()) {
+                return Ok(());
+            }
+        }
+        reader.consume(length);
+    }
 }
 
-fn find_first_marker(input: &Vec<char>, window_size: usize) -> usize {
-    input
-        .windows(window_size)
-        .position(|window| window.iter().unique().count() == window_size)
-        .unwrap()
-        + window_size
+pub fn first_marker<R: Read>(reader: R, window_size: usize) -> io::Result<Option<usize>> {
+    let mut first = None;
+    scan_markers(reader, window_size, |position| {
+        first = Some(position);
+        false
+    })?;
+
+    Ok(first)
+}
+
+#[aoc_generator(day06)]
+pub fn generate_input(input: &str) -> Vec<u8> {
+    input.as_bytes().to_vec()
 }
 
 #[aoc(day06, part1)]
-pub fn solve_part1(input: &Vec<char>) -> usize {
-    find_first_marker(input, 4)
+pub fn solve_part1(input: &[u8]) -> usize {
+    markers(input.iter().copied(), 4).next().unwrap()
 }
 
 #[aoc(day06, part2)]
-pub fn solve_part2(input: &Vec<char>) -> usize {
-    find_first_marker(input, 14)
+pub fn solve_part2(input: &[u8]) -> usize {
+    markers(input.iter().copied(), 14).next().unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn get_input() -> Vec<char> {
+    fn get_input() -> Vec<u8> {
         generate_input("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw")
     }
 
@@ -45,6 +155,34 @@ mod tests {
         assert_eq!(solve_part2(&input), 26);
     }
 
+    #[test]
+    fn example_all_markers() {
+        let found: Vec<usize> = markers(b"abcabbcdde".iter().copied(), 3).collect();
+        assert_eq!(found, vec![3, 4, 5, 8]);
+
+        assert_eq!(markers(b"aaaaaaaa".iter().copied(), 2).next(), None);
+        assert_eq!(markers(b"abc".iter().copied(), 4).next(), None);
+        assert_eq!(markers(b"".iter().copied(), 1).next(), None);
+        assert_eq!(markers(b"aab".iter().copied(), 1).count(), 3);
+    }
+
+    #[test]
+    fn example_reader() {
+        let input = get_input();
+
+        assert_eq!(first_marker(&input[..], 4).unwrap(), Some(11));
+        assert_eq!(first_marker(&input[..], 14).unwrap(), Some(26));
+        assert_eq!(first_marker(&b"abab"[..], 3).unwrap(), None);
+
+        let mut found = vec![];
+        scan_markers(&input[..], 4, |position| {
+            found.push(position);
+            true
+        })
+        .unwrap();
+        assert_eq!(found, markers(input.iter().copied(), 4).collect::<Vec<_>>());
+    }
+
     #[test]
     fn test_input_part1() {
         let input = generate_input(include_str!("../../input/2022/day6.txt"));
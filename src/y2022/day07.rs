@@ -1,114 +1,324 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-#[derive(PartialEq)]
+#[derive(Debug, PartialEq)]
 enum CommandLine {
     ChangeDirectory(String),
     List,
-    File(usize),
+    File { name: String, size: usize },
     Directory(String),
 }
 
 impl From<&str> for CommandLine {
     fn from(s: &str) -> Self {
-        if s.starts_with("$ ") {
-            let command = s.split_once(" ").unwrap().1;
-
+        if let Some(command) = s.strip_prefix("$ ") {
             match command {
                 "ls" => Self::List,
                 _ => {
-                    let to = command.split_once(" ").unwrap().1.to_string();
+                    let to = command.split_once(' ').unwrap().1.to_string();
                     Self::ChangeDirectory(to)
                 }
             }
         }
         // outputs
-        else if s.starts_with("dir") {
-            let name = s.split_once(" ").unwrap().1.to_string();
-            Self::Directory(name)
+        else if let Some(name) = s.strip_prefix("dir ") {
+            Self::Directory(name.to_string())
         } else {
-            Self::File(s.split_once(" ").unwrap().0.parse().unwrap())
+            let (size, name) = s.split_once(' ').unwrap();
+            Self::File {
+                name: name.to_string(),
+                size: size.parse().unwrap(),
+            }
         }
     }
 }
 
 #[derive(Debug)]
-enum Type {
-    Directory(String),
-    File(usize),
+enum Kind {
+    Directory(BTreeMap<String, usize>),
+    File,
+}
+
+#[derive(Debug)]
+struct Node {
+    name: String,
+    parent: usize,
+    kind: Kind,
+    // own size for files, total size of everything inside for directories
+    size: usize,
 }
 
+// Every file and directory lives in one arena and refers to the others by
+// index. The root is always at index 0 and is its own parent.
 #[derive(Debug)]
 pub struct FileSystem {
-    contents: HashMap<String, Vec<Type>>,
+    nodes: Vec<Node>,
 }
 
 impl FileSystem {
+    pub const ROOT: usize = 0;
+
     fn new() -> Self {
         Self {
-            contents: HashMap::new(),
+            nodes: vec![Node {
+                name: "/".to_string(),
+                parent: Self::ROOT,
+                kind: Kind::Directory(BTreeMap::new()),
+                size: 0,
+            }],
         }
     }
 
-    fn size_of(&self, directory: &str) -> usize {
-        let contents = self.contents.get(directory).unwrap();
-        contents
-            .iter()
-            .map(|content| match content {
-                Type::File(size) => *size,
-                Type::Directory(child) => {
-                    let directory = directory.to_string() + "/" + child;
-                    self.size_of(&directory)
-                }
+    fn children(&self, directory: usize) -> Option<&BTreeMap<String, usize>> {
+        match &self.nodes[directory].kind {
+            Kind::Directory(children) => Some(children),
+            Kind::File => None,
+        }
+    }
+
+    fn child(&self, directory: usize, name: &str) -> Option<usize> {
+        self.children(directory)?.get(name).copied()
+    }
+
+    // adds an entry unless one with the same name is already there, so
+    // listing a directory twice doesn't change anything
+    fn add(&mut self, directory: usize, name: &str, kind: Kind, size: usize) -> usize {
+        if let Some(existing) = self.child(directory, name) {
+            return existing;
+        }
+
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: directory,
+            kind,
+            size,
+        });
+        if let Kind::Directory(children) = &mut self.nodes[directory].kind {
+            children.insert(name.to_string(), id);
+        }
+
+        id
+    }
+
+    // resolves `cd` targets: `/`, `..`, absolute and relative paths
+    fn change_directory(&mut self, from: usize, to: &str) -> usize {
+        let start = if to.starts_with('/') {
+            Self::ROOT
+        } else {
+            from
+        };
+
+        to.split('/')
+            .filter(|part| !part.is_empty() && *part != ".")
+            .fold(start, |current, part| match part {
+                ".." => self.nodes[current].parent,
+                _ => self.add(current, part, Kind::Directory(BTreeMap::new()), 0),
             })
-            .sum::<usize>()
     }
+
+    // children are always created after their parent, so going backwards
+    // through the arena visits every node after everything inside it
+    fn compute_sizes(&mut self) {
+        for node in &mut self.nodes {
+            if let Kind::Directory(_) = node.kind {
+                node.size = 0;
+            }
+        }
+
+        for id in (1..self.nodes.len()).rev() {
+            let (size, parent) = (self.nodes[id].size, self.nodes[id].parent);
+            self.nodes[parent].size += size;
+        }
+    }
+
+    pub fn size_of(&self, id: usize) -> usize {
+        self.nodes[id].size
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.nodes[id].name
+    }
+
+    pub fn is_directory(&self, id: usize) -> bool {
+        matches!(self.nodes[id].kind, Kind::Directory(_))
+    }
+
+    pub fn directories(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(move |&id| self.is_directory(id))
+    }
+
+    pub fn path(&self, id: usize) -> String {
+        if id == Self::ROOT {
+            return "/".to_string();
+        }
+
+        let mut parts = vec![];
+        let mut current = id;
+        while current != Self::ROOT {
+            parts.push(self.name(current));
+            current = self.nodes[current].parent;
+        }
+        parts.reverse();
+
+        format!("/{}", parts.join("/"))
+    }
+
+    pub fn lookup(&self, path: &str) -> Option<usize> {
+        path.split('/')
+            .filter(|part| !part.is_empty())
+            .try_fold(Self::ROOT, |current, part| self.child(current, part))
+    }
+
+    // visits `id` and everything inside it, parents before children and
+    // children in name order, along with how deep each one is below `id`
+    fn walk<F>(&self, id: usize, depth: usize, visit: &mut F)
+    where
+        F: FnMut(usize, usize),
+    {
+        visit(id, depth);
+        if let Some(children) = self.children(id) {
+            for &child in children.values() {
+                self.walk(child, depth + 1, visit);
+            }
+        }
+    }
+
+    pub fn tree(&self) -> String {
+        let mut tree = String::new();
+        self.walk(Self::ROOT, 0, &mut |id, depth| {
+            let kind = if self.is_directory(id) { "dir" } else { "file" };
+            tree += &format!(
+                "{}- {} ({}, size={})\n",
+                "  ".repeat(depth),
+                self.name(id),
+                kind,
+                self.size_of(id)
+            );
+        });
+        tree
+    }
+
+    // paths and sizes of directories at most `max_depth` levels below the root
+    pub fn du(&self, max_depth: Option<usize>) -> Vec<(String, usize)> {
+        let mut usage = vec![];
+        self.walk(Self::ROOT, 0, &mut |id, depth| {
+            if self.is_directory(id) && max_depth.is_none_or(|max| depth <= max) {
+                usage.push((self.path(id), self.size_of(id)));
+            }
+        });
+        usage
+    }
+
+    pub fn find<P>(&self, predicate: P) -> Vec<usize>
+    where
+        P: Fn(usize) -> bool,
+    {
+        let mut found = vec![];
+        self.walk(Self::ROOT, 0, &mut |id, _| {
+            if predicate(id) {
+                found.push(id);
+            }
+        });
+        found
+    }
+
+    pub fn find_by_name(&self, glob: &str) -> Vec<usize> {
+        self.find(|id| id != Self::ROOT && glob_matches(glob, self.name(id)))
+    }
+
+    pub fn largest_directories(&self, n: usize) -> Vec<usize> {
+        let mut directories: Vec<usize> = self.directories().collect();
+        directories.sort_by_key(|&id| std::cmp::Reverse(self.size_of(id)));
+        directories.truncate(n);
+        directories
+    }
+
+    // the smallest directory that leaves `required` bytes free once deleted
+    pub fn smallest_directory_to_free(&self, disk_size: usize, required: usize) -> Option<usize> {
+        let free = disk_size.saturating_sub(self.size_of(Self::ROOT));
+        let to_delete = required.saturating_sub(free);
+
+        self.directories()
+            .filter(|&id| self.size_of(id) >= to_delete)
+            .min_by_key(|&id| self.size_of(id))
+    }
+
+    // a terminal session that lists every directory exactly once
+    pub fn to_transcript(&self) -> String {
+        let mut transcript = String::from("$ cd /\n");
+        self.write_listing(Self::ROOT, &mut transcript);
+        transcript
+    }
+
+    fn write_listing(&self, directory: usize, transcript: &mut String) {
+        let children = self.children(directory).unwrap();
+
+        *transcript += "$ ls\n";
+        for (name, &child) in children {
+            if self.is_directory(child) {
+                *transcript += &format!("dir {}\n", name);
+            } else {
+                *transcript += &format!("{} {}\n", self.size_of(child), name);
+            }
+        }
+
+        for (name, &child) in children.iter().filter(|(_, &c)| self.is_directory(c)) {
+            *transcript += &format!("$ cd {}\n", name);
+            self.write_listing(child, transcript);
+            *transcript += "$ cd ..\n";
+        }
+    }
+}
+
+// shell style wildcards: `*` matches any run of characters, `?` exactly one
+fn glob_matches(glob: &str, name: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // matches[j] is whether the glob so far matches the first j characters
+    let mut matches = vec![false; name.len() + 1];
+    matches[0] = true;
+    for &g in &glob {
+        let mut next = vec![false; name.len() + 1];
+        for j in 0..=name.len() {
+            next[j] = match g {
+                '*' => matches[j] || (j > 0 && next[j - 1]),
+                '?' => j > 0 && matches[j - 1],
+                _ => j > 0 && matches[j - 1] && name[j - 1] == g,
+            };
+        }
+        matches = next;
+    }
+
+    matches[name.len()]
 }
 
-fn parse_commands_into_filesystem(input: &Vec<CommandLine>) -> FileSystem {
-    // start from root / directory
-    let mut directories: Vec<String> = vec!["/".to_string()];
+fn parse_commands_into_filesystem(input: &[CommandLine]) -> FileSystem {
     let mut file_system = FileSystem::new();
+    let mut current = FileSystem::ROOT;
 
-    for line in input.iter() {
+    for line in input {
         match line {
             CommandLine::ChangeDirectory(to) => {
-                match to.as_str() {
-                    ".." => {
-                        directories.pop();
-                    }
-                    _ => directories.push(to.clone()),
-                };
+                current = file_system.change_directory(current, to);
             }
             CommandLine::List => {}
-            CommandLine::File(size) => {
-                let current_dir = directories.join("/");
-                file_system
-                    .contents
-                    .entry(current_dir)
-                    .and_modify(|contents| contents.push(Type::File(*size)))
-                    .or_insert_with(|| vec![Type::File(*size)]);
+            CommandLine::File { name, size } => {
+                file_system.add(current, name, Kind::File, *size);
             }
-            CommandLine::Directory(dir) => {
-                let current_dir = directories.join("/");
-                file_system
-                    .contents
-                    .entry(current_dir.clone())
-                    .and_modify(|contents| contents.push(Type::Directory(dir.clone())))
-                    .or_insert_with(|| vec![Type::Directory(dir.clone())]);
+            CommandLine::Directory(name) => {
+                file_system.add(current, name, Kind::Directory(BTreeMap::new()), 0);
             }
         }
     }
+
+    file_system.compute_sizes();
     file_system
 }
 
 #[aoc_generator(day07)]
 pub fn generate_input(input: &str) -> FileSystem {
-    let command_lines: Vec<CommandLine> = input
-        .lines()
-        // skip the first line since we start from the root
-        .skip(1)
-        .map(CommandLine::from)
-        .collect();
+    let command_lines: Vec<CommandLine> = input.lines().map(CommandLine::from).collect();
 
     parse_commands_into_filesystem(&command_lines)
 }
@@ -116,26 +326,19 @@ pub fn generate_input(input: &str) -> FileSystem {
 #[aoc(day07, part1)]
 pub fn solve_part1(input: &FileSystem) -> usize {
     input
-        .contents
-        .keys()
-        .map(|directory| input.size_of(&directory))
+        .directories()
+        .map(|directory| input.size_of(directory))
         .filter(|size| *size <= 100_000)
         .sum()
 }
 
 #[aoc(day07, part2)]
 pub fn solve_part2(input: &FileSystem) -> usize {
-    let used_size = input.size_of("/");
-    let max_size = 70_000_000 - 30_000_000;
-    let smallest_to_delete = used_size - max_size;
+    let directory = input
+        .smallest_directory_to_free(70_000_000, 30_000_000)
+        .unwrap();
 
-    input
-        .contents
-        .keys()
-        .map(|directory| input.size_of(directory))
-        .filter(|size| *size >= smallest_to_delete)
-        .min()
-        .unwrap()
+    input.size_of(directory)
 }
 
 #[cfg(test)]
@@ -184,6 +387,151 @@ $ ls
         assert_eq!(solve_part2(&input), 24933642);
     }
 
+    #[test]
+    fn example_tree() {
+        let input = get_input();
+
+        let e = input.lookup("/a/e").unwrap();
+        assert_eq!(input.path(e), "/a/e");
+        assert_eq!(input.size_of(e), 584);
+        assert_eq!(input.size_of(input.lookup("/a").unwrap()), 94853);
+        assert_eq!(input.size_of(input.lookup("d/d.log").unwrap()), 8033020);
+        assert!(!input.is_directory(input.lookup("/b.txt").unwrap()));
+        assert_eq!(input.lookup("/a/x"), None);
+        assert_eq!(input.directories().count(), 4);
+    }
+
+    #[test]
+    fn example_navigation() {
+        let input = generate_input(
+            "$ cd /
+$ ls
+dir a
+10 top
+$ cd a
+$ ls
+20 inner
+$ ls
+20 inner
+$ cd /
+$ cd a/b
+$ ls
+30 deep
+$ cd ../..
+$ cd ..
+$ ls
+10 top
+$ cd /a/b/
+$ cd ./c
+$ ls
+40 deeper",
+        );
+
+        assert_eq!(input.size_of(FileSystem::ROOT), 100);
+        assert_eq!(input.size_of(input.lookup("/a").unwrap()), 90);
+        assert_eq!(input.size_of(input.lookup("/a/b").unwrap()), 70);
+        assert_eq!(
+            input.path(input.lookup("a/b/c/deeper").unwrap()),
+            "/a/b/c/deeper"
+        );
+        assert_eq!(input.name(input.lookup("/a/inner").unwrap()), "inner");
+    }
+
+    #[test]
+    fn example_tree_rendering() {
+        let input = get_input();
+
+        assert_eq!(
+            input.tree(),
+            "- / (dir, size=48381165)
+  - a (dir, size=94853)
+    - e (dir, size=584)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir, size=24933642)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+"
+        );
+    }
+
+    #[test]
+    fn example_queries() {
+        let input = get_input();
+        let paths =
+            |ids: Vec<usize>| -> Vec<String> { ids.iter().map(|&id| input.path(id)).collect() };
+
+        assert_eq!(
+            input.du(Some(1)),
+            vec![
+                ("/".to_string(), 48381165),
+                ("/a".to_string(), 94853),
+                ("/d".to_string(), 24933642)
+            ]
+        );
+        assert_eq!(input.du(None).len(), 4);
+        assert_eq!(input.du(Some(0)).len(), 1);
+
+        assert_eq!(
+            paths(input.find_by_name("d.*")),
+            vec!["/d/d.ext", "/d/d.log"]
+        );
+        assert_eq!(
+            paths(input.find_by_name("?")),
+            vec!["/a", "/a/e", "/a/e/i", "/a/f", "/a/g", "/d", "/d/j", "/d/k"]
+        );
+        assert_eq!(
+            paths(input.find(|id| !input.is_directory(id) && input.size_of(id) > 8_000_000)),
+            vec!["/b.txt", "/c.dat", "/d/d.log"]
+        );
+        assert_eq!(paths(input.largest_directories(2)), vec!["/", "/d"]);
+
+        assert_eq!(
+            input.smallest_directory_to_free(70_000_000, 30_000_000),
+            input.lookup("/d")
+        );
+        assert_eq!(
+            input.smallest_directory_to_free(48_500_000, 200_000),
+            input.lookup("/a")
+        );
+        assert_eq!(
+            input.smallest_directory_to_free(100_000_000, 1),
+            input.lookup("/a/e")
+        );
+        assert_eq!(
+            input.smallest_directory_to_free(40_000_000, 50_000_000),
+            None
+        );
+
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("a*b?c", "axxbyc"));
+        assert!(!glob_matches("a*b?c", "axxbc"));
+    }
+
+    #[test]
+    fn example_transcript_round_trip() {
+        let input = get_input();
+
+        let transcript = input.to_transcript();
+        let parsed = generate_input(&transcript);
+        assert_eq!(parsed.tree(), input.tree());
+        assert_eq!(parsed.to_transcript(), transcript);
+    }
+
+    #[test]
+    fn test_input_transcript_round_trip() {
+        let input = generate_input(include_str!("../../input/2022/day7.txt"));
+        let parsed = generate_input(&input.to_transcript());
+
+        assert_eq!(parsed.tree(), input.tree());
+    }
+
     #[test]
     fn test_input_part1() {
         let input = generate_input(include_str!("../../input/2022/day7.txt"));
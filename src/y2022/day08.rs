@@ -1,150 +1,114 @@
-use itertools::Itertools;
-
-#[derive(Debug)]
-struct Tree(u32);
-
-impl From<char> for Tree {
-    fn from(c: char) -> Self {
-        Self(c.to_digit(10).unwrap())
-    }
-}
-
+// Tree heights stored row by row; positions are (row, column) pairs.
 #[derive(Debug)]
 pub struct Grid {
-    trees: Vec<Vec<Tree>>,
+    heights: Vec<u8>,
+    width: usize,
+    height: usize,
 }
 
 impl Grid {
-    fn get(&self, x: usize, y: usize) -> u32 {
-        self.trees[x][y].0
-    }
-
-    fn is_tree_visible_from_right(&self, x: usize, y: usize, height: u32) -> bool {
-        ((x + 1)..self.len())
-            .into_iter()
-            .all(|i| self.get(i, y) < height)
-    }
-
-    fn is_tree_visible_from_left(&self, x: usize, y: usize, height: u32) -> bool {
-        (0..x).into_iter().all(|i| self.get(i, y) < height)
-    }
-
-    fn is_tree_visible_from_top(&self, x: usize, y: usize, height: u32) -> bool {
-        (0..y).into_iter().all(|j| self.get(x, j) < height)
-    }
-
-    fn is_tree_visible_from_bottom(&self, x: usize, y: usize, height: u32) -> bool {
-        ((y + 1)..self.len())
-            .into_iter()
-            .all(|j| self.get(x, j) < height)
-    }
-
-    fn is_tree_visible(&self, x: usize, y: usize) -> bool {
-        if x == 0 || y == 0 || x == self.len() - 1 || y == self.len() - 1 {
-            return true;
+    fn position(&self, index: usize) -> (usize, usize) {
+        (index / self.width, index % self.width)
+    }
+
+    // Every row and column as a list of indices, once in each direction, so
+    // looking at the trees "behind" an index covers all four lines of sight.
+    fn lines_of_sight(&self) -> impl Iterator<Item = Vec<usize>> + '_ {
+        let rows = (0..self.height).map(move |row| {
+            (0..self.width)
+                .map(|column| row * self.width + column)
+                .collect::<Vec<_>>()
+        });
+        let columns = (0..self.width).map(move |column| {
+            (0..self.height)
+                .map(|row| row * self.width + column)
+                .collect::<Vec<_>>()
+        });
+
+        rows.chain(columns).flat_map(|line| {
+            let reversed = line.iter().rev().copied().collect();
+            vec![line, reversed]
+        })
+    }
+
+    // positions of the trees visible from outside the grid, in reading order
+    pub fn visible_trees(&self) -> Vec<(usize, usize)> {
+        let mut visible = vec![false; self.heights.len()];
+
+        for line in self.lines_of_sight() {
+            let mut tallest = None;
+            for index in line {
+                if tallest < Some(self.heights[index]) {
+                    visible[index] = true;
+                    tallest = Some(self.heights[index]);
+                }
+            }
         }
 
-        let height = self.get(x, y);
-
-        self.is_tree_visible_from_bottom(x, y, height)
-            || self.is_tree_visible_from_left(x, y, height)
-            || self.is_tree_visible_from_right(x, y, height)
-            || self.is_tree_visible_from_top(x, y, height)
-    }
-
-    fn scenic_score_for_tree_up(&self, x: usize, y: usize, height: u32) -> usize {
-        let score = (0..x)
-            .rev()
-            .take_while(|&i| self.get(i, y) < height)
-            .count();
-
-        if score != x {
-            score + 1
-        } else {
-            score
+        (0..self.heights.len())
+            .filter(|&index| visible[index])
+            .map(|index| self.position(index))
+            .collect()
+    }
+
+    pub fn scenic_scores(&self) -> Vec<usize> {
+        let mut scores = vec![1; self.heights.len()];
+
+        for line in self.lines_of_sight() {
+            // positions along the line that could still block the view of a
+            // later tree, their heights decreasing towards the top
+            let mut blockers: Vec<usize> = vec![];
+            for (position, &index) in line.iter().enumerate() {
+                while let Some(&top) = blockers.last() {
+                    if self.heights[line[top]] >= self.heights[index] {
+                        break;
+                    }
+                    blockers.pop();
+                }
+
+                scores[index] *= position - blockers.last().copied().unwrap_or(0);
+                blockers.push(position);
+            }
         }
-    }
-
-    fn scenic_score_for_tree_down(&self, x: usize, y: usize, height: u32) -> usize {
-        let score = ((x + 1)..self.len())
-            .take_while(|&i| self.get(i, y) < height)
-            .count();
-
-        if score != self.len() - x - 1 {
-            score + 1
-        } else {
-            score
-        }
-    }
-    fn scenic_score_for_tree_left(&self, x: usize, y: usize, height: u32) -> usize {
-        let score = (0..y)
-            .rev()
-            .take_while(|&j| self.get(x, j) < height)
-            .count();
-
-        if score != y {
-            score + 1
-        } else {
-            score
-        }
-    }
-
-    fn scenic_score_for_tree_right(&self, x: usize, y: usize, height: u32) -> usize {
-        let score = ((y + 1)..self.len())
-            .take_while(|&j| self.get(x, j) < height)
-            .count();
-
-        if score != self.len() - y - 1 {
-            score + 1
-        } else {
-            score
-        }
-    }
-
-    fn scenic_score_for_tree(&self, x: usize, y: usize) -> usize {
-        let height = self.get(x, y);
 
-        self.scenic_score_for_tree_left(x, y, height)
-            * self.scenic_score_for_tree_right(x, y, height)
-            * self.scenic_score_for_tree_up(x, y, height)
-            * self.scenic_score_for_tree_down(x, y, height)
+        scores
     }
 
-    fn len(&self) -> usize {
-        self.trees.len()
+    // the tree with the highest scenic score, the first one in reading order on ties
+    pub fn most_scenic_tree(&self) -> Option<((usize, usize), usize)> {
+        self.scenic_scores()
+            .into_iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|&(_, score)| score)
+            .map(|(index, score)| (self.position(index), score))
     }
 }
 
 #[aoc_generator(day08)]
 pub fn generate_input(input: &str) -> Grid {
+    let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+    let width = rows.first().map_or(0, |row| row.len());
+    assert!(
+        rows.iter().all(|row| row.len() == width),
+        "every row of the forest needs the same number of trees"
+    );
+
     Grid {
-        trees: input
-            .lines()
-            .map(|l| l.chars().map(Tree::from).collect_vec())
-            .collect_vec(),
+        heights: rows.concat().into_iter().map(|c| c - b'0').collect(),
+        width,
+        height: rows.len(),
     }
 }
 
-fn permutate_indices(max: usize) -> impl Iterator<Item = (usize, usize)> {
-    (0..max)
-        .zip(0..max)
-        .chain((0..max).permutations(2).map(|v| (v[0], v[1])))
-        .sorted()
-}
-
 #[aoc(day08, part1)]
 pub fn solve_part1(input: &Grid) -> usize {
-    permutate_indices(input.len())
-        .filter(|(x, y)| input.is_tree_visible(*x, *y))
-        .count()
+    input.visible_trees().len()
 }
 
 #[aoc(day08, part2)]
 pub fn solve_part2(input: &Grid) -> usize {
-    permutate_indices(input.len())
-        .map(|(x, y)| input.scenic_score_for_tree(x, y))
-        .max()
-        .unwrap_or(0)
+    input.most_scenic_tree().map_or(0, |(_, score)| score)
 }
 
 #[cfg(test)]
@@ -181,6 +145,39 @@ mod tests {
         let input = get_input();
 
         assert_eq!(solve_part2(&input), 8);
+        assert_eq!(input.most_scenic_tree(), Some(((3, 2), 8)));
+    }
+
+    #[test]
+    fn example_rectangular() {
+        let input = generate_input("3037\n2551\n6533");
+
+        assert_eq!(
+            input.visible_trees(),
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (0, 3),
+                (1, 0),
+                (1, 1),
+                (1, 2),
+                (1, 3),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (2, 3)
+            ]
+        );
+        assert_eq!(
+            input.scenic_scores(),
+            vec![0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(input.most_scenic_tree(), Some(((1, 1), 1)));
+
+        let tall = generate_input("1\n2\n1\n3");
+        assert_eq!(tall.visible_trees().len(), 4);
+        assert_eq!(tall.most_scenic_tree(), Some(((0, 0), 0)));
     }
 
     #[test]